@@ -0,0 +1,9 @@
+-- Mirror the oracle-adapter's on-chain reputation counters (`orc_rep` events)
+ALTER TABLE oracles
+    ADD COLUMN disagreements INTEGER DEFAULT 0,
+    ADD COLUMN challenges_lost INTEGER DEFAULT 0,
+    ADD COLUMN last_active_at TIMESTAMP WITH TIME ZONE;
+
+COMMENT ON COLUMN oracles.successful_confirmations IS 'Live confirmations that matched the majority payload on-chain';
+COMMENT ON COLUMN oracles.disagreements IS 'Live confirmations that differed from the majority payload on-chain';
+COMMENT ON COLUMN oracles.challenges_lost IS 'Challenges upheld against the oracle''s confirmations';
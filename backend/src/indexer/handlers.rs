@@ -38,6 +38,7 @@ impl EventHandler {
             "collateral" => self.handle_collateral_event(&event_name, &data).await?,
             "escrow" => self.handle_escrow_event(&event_name, &data).await?,
             "loan" => self.handle_loan_event(&event_name, &data).await?,
+            "oracle" => self.handle_oracle_event(&event_name, &data).await?,
             _ => info!("Unknown contract type: {}", contract_type),
         }
 
@@ -189,6 +190,48 @@ impl EventHandler {
         Ok(())
     }
 
+    async fn handle_oracle_event(&self, name: &str, data: &ScVal) -> Result<()> {
+        match name {
+            "orc_rep" => {
+                if let ScVal::Vec(Some(args)) = data {
+                    if args.len() < 6 { return Err(anyhow!("Invalid args length for orc_rep")); }
+                    let oracle = scval_to_address(&args[0])?;
+                    let confirmations = scval_to_u64(&args[1])? as i32;
+                    let agreements = scval_to_u64(&args[2])? as i32;
+                    let disagreements = scval_to_u64(&args[3])? as i32;
+                    let challenges_lost = scval_to_u64(&args[4])? as i32;
+                    let last_active = scval_to_u64(&args[5])? as i64;
+
+                    // Reputation is the share of scored confirmations that agreed with the majority
+                    sqlx::query(
+                        r#"
+                        UPDATE oracles
+                        SET total_confirmations = $2,
+                            successful_confirmations = $3,
+                            disagreements = $4,
+                            challenges_lost = $5,
+                            last_active_at = to_timestamp($6),
+                            reputation_score = CASE WHEN $3 + $4 > 0
+                                THEN ROUND(100.0 * $3 / ($3 + $4), 2)
+                                ELSE reputation_score END
+                        WHERE address = $1
+                        "#
+                    )
+                    .bind(oracle)
+                    .bind(confirmations)
+                    .bind(agreements)
+                    .bind(disagreements)
+                    .bind(challenges_lost)
+                    .bind(last_active)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
     async fn get_user_id_by_address(&self, address: &str) -> Result<Option<Uuid>> {
         let rec = sqlx::query("SELECT id FROM users WHERE stellar_address = $1")
             .bind(address)
@@ -76,6 +76,7 @@ async fn main() {
     let collateral_id = std::env::var("COLLATERAL_CONTRACT_ID").unwrap_or_else(|_| contract_id.clone());
     let escrow_id = std::env::var("ESCROW_CONTRACT_ID").unwrap_or_else(|_| contract_id.clone());
     let loan_id = std::env::var("LOAN_CONTRACT_ID").unwrap_or_else(|_| contract_id.clone());
+    let oracle_id = std::env::var("ORACLE_CONTRACT_ID").unwrap_or_else(|_| contract_id.clone());
     
     let soroban_rpc_url = std::env::var("SOROBAN_RPC_URL")
         .unwrap_or_else(|_| "https://soroban-testnet.stellar.org".to_string());
@@ -140,6 +141,7 @@ async fn main() {
     contracts_map.insert("collateral".to_string(), collateral_id);
     contracts_map.insert("escrow".to_string(), escrow_id);
     contracts_map.insert("loan".to_string(), loan_id);
+    contracts_map.insert("oracle".to_string(), oracle_id);
 
     let indexer_service = Arc::new(indexer::IndexerService::new(
         soroban_rpc_url,
@@ -448,5 +448,7 @@ pub struct OracleMetrics {
     pub active_oracles: i64,
     pub total_confirmations: i64,
     pub successful_confirmations: i64,
+    pub disagreements: i64,
+    pub challenges_lost: i64,
     pub average_reputation_score: f64,
 }
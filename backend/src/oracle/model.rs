@@ -103,21 +103,51 @@ impl OraclePayload {
         if self.confirmation_id.is_empty() {
             return Err("confirmation_id is required".to_string());
         }
-        Ok(())
+        self.data.validate()
     }
 }
 
+/// Shipment lifecycle status, mirrors `ShipmentStatus` in the oracle-adapter contract
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShipmentStatus {
+    Booked = 0,
+    Departed = 1,
+    InTransit = 2,
+    Arrived = 3,
+    Delivered = 4,
+}
+
 /// Type-specific oracle payload data
+///
+/// The shipment, delivery, quality and valuation variants mirror the
+/// `ConfirmationPayload` variants in the oracle-adapter contract field for field;
+/// hashes are hex-encoded 32-byte values. IoT and manual readings have no typed
+/// on-chain counterpart and are submitted as `Custom` payloads.
+///
+/// The shipment variant replaces the former `shipping` payload type, which is
+/// still accepted as an alias. Its fields changed (`tracking_number`, `carrier`
+/// and `location` became `tracking_hash`, `carrier_code` and `port`), so older
+/// clients must send the new fields.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "payload_type")]
 pub enum OraclePayloadData {
-    #[serde(rename = "shipping")]
-    Shipping {
-        tracking_number: String,
-        carrier: String,
-        status: String,
-        location: Option<String>,
+    #[serde(rename = "shipment", alias = "shipping")]
+    Shipment {
+        carrier_code: String,
+        tracking_hash: String,
+        port: String,
+        status: ShipmentStatus,
+    },
+    #[serde(rename = "delivery")]
+    Delivery { receiver_signoff_hash: String },
+    #[serde(rename = "quality")]
+    Quality {
+        grade: u32,
+        inspection_cert_hash: String,
     },
+    #[serde(rename = "valuation")]
+    Valuation { amount: i128, currency: String },
     #[serde(rename = "iot")]
     Iot {
         device_id: String,
@@ -133,6 +163,55 @@ pub enum OraclePayloadData {
     },
 }
 
+impl OraclePayloadData {
+    /// Applies the checks the contract enforces in `confirm_event`, so a bad
+    /// payload is rejected before a transaction is paid for.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            OraclePayloadData::Shipment {
+                carrier_code,
+                tracking_hash,
+                port,
+                ..
+            } => {
+                if carrier_code.is_empty() || port.is_empty() {
+                    return Err("carrier_code and port are required".to_string());
+                }
+                validate_hash("tracking_hash", tracking_hash)
+            }
+            OraclePayloadData::Delivery {
+                receiver_signoff_hash,
+            } => validate_hash("receiver_signoff_hash", receiver_signoff_hash),
+            OraclePayloadData::Quality {
+                grade,
+                inspection_cert_hash,
+            } => {
+                if *grade > 100 {
+                    return Err("grade must be between 0 and 100".to_string());
+                }
+                validate_hash("inspection_cert_hash", inspection_cert_hash)
+            }
+            OraclePayloadData::Valuation { amount, currency } => {
+                if *amount <= 0 {
+                    return Err("amount must be positive".to_string());
+                }
+                if currency.is_empty() {
+                    return Err("currency is required".to_string());
+                }
+                Ok(())
+            }
+            OraclePayloadData::Iot { .. } | OraclePayloadData::Manual { .. } => Ok(()),
+        }
+    }
+}
+
+fn validate_hash(field: &str, value: &str) -> Result<(), String> {
+    if value.len() != 64 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("{} must be a hex-encoded 32-byte hash", field));
+    }
+    Ok(())
+}
+
 /// Response DTO for POST /oracle/confirm
 #[derive(Debug, Serialize)]
 pub struct OracleConfirmResponse {
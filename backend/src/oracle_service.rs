@@ -165,6 +165,8 @@ impl OracleService {
             active_oracles: Option<i64>,
             total_confirmations: Option<i64>,
             successful_confirmations: Option<i64>,
+            disagreements: Option<i64>,
+            challenges_lost: Option<i64>,
             average_reputation_score: Option<f64>,
         }
 
@@ -175,6 +177,8 @@ impl OracleService {
                 (SELECT COUNT(*) FROM oracles WHERE is_active = true) as active_oracles,
                 (SELECT COALESCE(SUM(total_confirmations), 0) FROM oracles) as total_confirmations,
                 (SELECT COALESCE(SUM(successful_confirmations), 0) FROM oracles) as successful_confirmations,
+                (SELECT COALESCE(SUM(disagreements), 0) FROM oracles) as disagreements,
+                (SELECT COALESCE(SUM(challenges_lost), 0) FROM oracles) as challenges_lost,
                 (SELECT COALESCE(AVG(reputation_score), 0) FROM oracles WHERE reputation_score IS NOT NULL) as average_reputation_score
             "#
         )
@@ -186,6 +190,8 @@ impl OracleService {
             active_oracles: metrics.active_oracles.unwrap_or(0),
             total_confirmations: metrics.total_confirmations.unwrap_or(0),
             successful_confirmations: metrics.successful_confirmations.unwrap_or(0),
+            disagreements: metrics.disagreements.unwrap_or(0),
+            challenges_lost: metrics.challenges_lost.unwrap_or(0),
             average_reputation_score: metrics.average_reputation_score.unwrap_or(0.0),
         })
     }
@@ -5,7 +5,9 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Symbol,
+};
 
 /// Contract errors
 #[contracttype]
@@ -18,6 +20,12 @@ pub enum ContractError {
     CollateralNotFound = 5,
     CollateralLocked = 6,
     DuplicateMetadata = 7,
+    CollateralNotLocked = 8,
+    ExceedsCollateralValue = 9,
+    CollateralFullySeized = 10,
+    CollateralRealized = 11,
+    LockNotHeld = 12,
+    EblNotHeld = 13,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -45,6 +53,16 @@ pub struct Collateral {
     pub registered_at: u64,
     pub last_valuation_ts: u64,
     pub locked: bool,
+    /// Contract holding the lock (escrow or factoring manager), if locked
+    pub lock_holder: Option<Address>,
+    /// Escrow or assignment ID the holder locked the collateral for
+    pub lock_ref: u64,
+}
+
+/// EblRegistry interface
+#[contractclient(name = "EblRegistryClient")]
+pub trait EblRegistry {
+    fn get_holder(env: Env, ebl_id: u64) -> Option<Address>;
 }
 
 /// Main contract for collateral registry operations
@@ -133,6 +151,8 @@ impl CollateralRegistry {
             registered_at: current_ts,
             last_valuation_ts: current_ts,
             locked: false,
+            lock_holder: None,
+            lock_ref: 0,
         };
 
         // Store collateral
@@ -157,12 +177,24 @@ impl CollateralRegistry {
 
     /// Lock collateral (only callable by EscrowManager contract)
     ///
+    /// The escrow manager becomes the lock holder for `escrow_id`; only that
+    /// escrow's unlock, or the risk engine acting on its position, frees it.
+    /// Collateral backed by an eBL can only be locked while the lender holds
+    /// the eBL.
+    ///
     /// # Arguments
     /// * `id` - Collateral ID to lock
+    /// * `escrow_id` - Escrow the collateral backs
+    /// * `lender` - Party financing the escrow
     ///
     /// # Events
     /// Emits `CollateralLocked` event
-    pub fn lock_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+    pub fn lock_collateral(
+        env: Env,
+        id: u64,
+        escrow_id: u64,
+        lender: Address,
+    ) -> Result<(), ContractError> {
         // Only escrow manager can lock collateral
         let escrow_manager: Address = env
             .storage()
@@ -182,12 +214,24 @@ impl CollateralRegistry {
             return Err(ContractError::CollateralLocked);
         }
 
-        collateral.locked = true;
+        // A paid invoice has no claim left to pledge
+        if Self::is_realized(env.clone(), id) {
+            return Err(ContractError::CollateralRealized);
+        }
+
+        // Seized shares belong to liquidators and cannot back a new deal
+        if Self::get_pledgeable_value(env.clone(), id) <= 0 {
+            return Err(ContractError::CollateralFullySeized);
+        }
+
+        Self::check_ebl_holder(&env, id, &lender)?;
+
+        Self::set_lock(&mut collateral, &escrow_manager, escrow_id);
         env.storage().persistent().set(&id, &collateral);
 
         env.events().publish(
             (symbol_short!("coll_lock"),),
-            (id,),
+            (id, escrow_manager, escrow_id),
         );
 
         Ok(())
@@ -195,12 +239,15 @@ impl CollateralRegistry {
 
     /// Unlock collateral (only callable by EscrowManager contract)
     ///
+    /// Fails if the collateral is locked for anything but `escrow_id`.
+    ///
     /// # Arguments
     /// * `id` - Collateral ID to unlock
+    /// * `escrow_id` - Escrow the collateral was locked for
     ///
     /// # Events
     /// Emits `CollateralUnlocked` event
-    pub fn unlock_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+    pub fn unlock_collateral(env: Env, id: u64, escrow_id: u64) -> Result<(), ContractError> {
         // Only escrow manager can unlock collateral
         let escrow_manager: Address = env
             .storage()
@@ -220,7 +267,364 @@ impl CollateralRegistry {
             return Ok(()); // Already unlocked
         }
 
-        collateral.locked = false;
+        Self::check_lock(&collateral, &escrow_manager, escrow_id)?;
+        Self::clear_lock(&mut collateral);
+        env.storage().persistent().set(&id, &collateral);
+
+        env.events().publish(
+            (symbol_short!("coll_unlk"),),
+            (id,),
+        );
+
+        Ok(())
+    }
+
+    /// Transfer ownership of locked collateral (only callable by EscrowManager contract)
+    ///
+    /// Used when a dispute ruling forfeits the collateral to the other party.
+    /// The collateral stays locked; the escrow manager unlocks it afterwards.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to transfer
+    /// * `escrow_id` - Escrow the collateral is locked for
+    /// * `new_owner` - Address receiving ownership
+    ///
+    /// # Events
+    /// Emits `coll_xfer` with (id, previous_owner, new_owner)
+    pub fn transfer_collateral(
+        env: Env,
+        id: u64,
+        escrow_id: u64,
+        new_owner: Address,
+    ) -> Result<(), ContractError> {
+        let escrow_manager: Address = env
+            .storage()
+            .instance()
+            .get(&Symbol::new(&env, "escrow_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        escrow_manager.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        Self::check_lock(&collateral, &escrow_manager, escrow_id)?;
+
+        let previous_owner = collateral.owner.clone();
+        collateral.owner = new_owner.clone();
+        env.storage().persistent().set(&id, &collateral);
+
+        env.events().publish(
+            (symbol_short!("coll_xfer"),),
+            (id, previous_owner, new_owner),
+        );
+
+        Ok(())
+    }
+
+    /// Assign collateral to a factor (only callable by Factoring Manager)
+    ///
+    /// Locks the collateral for `assignment_id` and makes the assignee its
+    /// owner, so an assigned invoice cannot be financed a second time.
+    /// Collateral backed by an eBL requires the assignee to hold the eBL.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID being assigned
+    /// * `assignment_id` - Assignment in the factoring manager
+    /// * `assignee` - Factor taking ownership
+    ///
+    /// # Events
+    /// Emits `coll_asgn` with (id, previous_owner, assignee)
+    pub fn assign_collateral(
+        env: Env,
+        id: u64,
+        assignment_id: u64,
+        assignee: Address,
+    ) -> Result<(), ContractError> {
+        let factoring_manager: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("fact_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        factoring_manager.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        if collateral.expiry_ts <= env.ledger().timestamp() {
+            return Err(ContractError::CollateralExpired);
+        }
+
+        if Self::is_realized(env.clone(), id) {
+            return Err(ContractError::CollateralRealized);
+        }
+
+        if Self::get_pledgeable_value(env.clone(), id) <= 0 {
+            return Err(ContractError::CollateralFullySeized);
+        }
+
+        Self::check_ebl_holder(&env, id, &assignee)?;
+
+        let previous_owner = collateral.owner.clone();
+        collateral.owner = assignee.clone();
+        Self::set_lock(&mut collateral, &factoring_manager, assignment_id);
+        env.storage().persistent().set(&id, &collateral);
+
+        env.events().publish(
+            (symbol_short!("coll_asgn"),),
+            (id, previous_owner, assignee),
+        );
+
+        Ok(())
+    }
+
+    /// Release assigned collateral once the invoice is written off
+    /// (only callable by Factoring Manager)
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to release
+    /// * `assignment_id` - Assignment the collateral was locked for
+    ///
+    /// # Events
+    /// Emits `coll_unlk` event
+    pub fn release_assignment(env: Env, id: u64, assignment_id: u64) -> Result<(), ContractError> {
+        let factoring_manager: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("fact_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        factoring_manager.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        Self::check_lock(&collateral, &factoring_manager, assignment_id)?;
+        Self::clear_lock(&mut collateral);
+        env.storage().persistent().set(&id, &collateral);
+
+        env.events().publish(
+            (symbol_short!("coll_unlk"),),
+            (id,),
+        );
+
+        Ok(())
+    }
+
+    /// Release assigned collateral once the invoice is paid in full and mark
+    /// it realized (only callable by Factoring Manager)
+    ///
+    /// A realized invoice keeps its record but can no longer be locked or
+    /// assigned, and has no pledgeable value.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to settle
+    /// * `assignment_id` - Assignment the collateral was locked for
+    ///
+    /// # Events
+    /// Emits `coll_real` event
+    pub fn settle_assignment(env: Env, id: u64, assignment_id: u64) -> Result<(), ContractError> {
+        let factoring_manager: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("fact_mgr"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        factoring_manager.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        Self::check_lock(&collateral, &factoring_manager, assignment_id)?;
+        Self::clear_lock(&mut collateral);
+        env.storage().persistent().set(&id, &collateral);
+        env.storage().persistent().set(&(symbol_short!("realized"), id), &true);
+
+        env.events().publish(
+            (symbol_short!("coll_real"),),
+            (id,),
+        );
+
+        Ok(())
+    }
+
+    /// Record the eBL backing a collateral (only callable by eBL Registry)
+    ///
+    /// From then on the collateral can only be locked or assigned to the
+    /// party holding the eBL.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID
+    /// * `ebl_id` - eBL in the eBL registry
+    ///
+    /// # Events
+    /// Emits `coll_ebl` with (id, ebl_id)
+    pub fn link_ebl(env: Env, id: u64, ebl_id: u64) -> Result<(), ContractError> {
+        let ebl_registry = Self::ebl_registry(&env)?;
+        ebl_registry.require_auth();
+
+        if !env.storage().persistent().has(&id) {
+            return Err(ContractError::CollateralNotFound);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ebl"), id), &ebl_id);
+
+        env.events().publish(
+            (symbol_short!("coll_ebl"),),
+            (id, ebl_id),
+        );
+
+        Ok(())
+    }
+
+    /// Mark collateral realized once its eBL has been surrendered against
+    /// delivery (only callable by eBL Registry)
+    ///
+    /// The goods have left the carrier's custody, so the record can no
+    /// longer be locked or assigned and has no pledgeable value.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to realize
+    ///
+    /// # Events
+    /// Emits `coll_real` event
+    pub fn realize_collateral(env: Env, id: u64) -> Result<(), ContractError> {
+        let ebl_registry = Self::ebl_registry(&env)?;
+        ebl_registry.require_auth();
+
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        env.storage().persistent().set(&(symbol_short!("realized"), id), &true);
+
+        env.events().publish(
+            (symbol_short!("coll_real"),),
+            (id,),
+        );
+
+        Ok(())
+    }
+
+    /// Seize a share of locked collateral for a liquidator (only callable by Risk Engine)
+    ///
+    /// Seized shares are denominated in face value. The owner keeps whatever
+    /// has not been seized. The collateral must be locked by the escrow
+    /// manager for the escrow backing the liquidated position.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID being liquidated
+    /// * `escrow_id` - Escrow backing the position
+    /// * `recipient` - Address receiving the seized share
+    /// * `value` - Face value to seize (must be > 0)
+    ///
+    /// # Events
+    /// Emits `CollateralSeized` event
+    pub fn seize_collateral(
+        env: Env,
+        id: u64,
+        escrow_id: u64,
+        recipient: Address,
+        value: i128,
+    ) -> Result<(), ContractError> {
+        // Only risk engine can seize collateral
+        let risk_engine: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("risk_eng"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        risk_engine.require_auth();
+
+        if value <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        Self::check_lock(&collateral, &Self::escrow_manager(&env)?, escrow_id)?;
+
+        let seized = Self::get_seized_value(env.clone(), id);
+        if seized + value > collateral.face_value {
+            return Err(ContractError::ExceedsCollateralValue);
+        }
+
+        let share_key = (symbol_short!("share"), id, recipient.clone());
+        let share: i128 = env.storage().persistent().get(&share_key).unwrap_or(0);
+        env.storage().persistent().set(&share_key, &(share + value));
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("seized"), id), &(seized + value));
+
+        env.events().publish(
+            (symbol_short!("coll_szd"),),
+            (id, recipient, value),
+        );
+
+        Ok(())
+    }
+
+    /// Unlock collateral after liquidation (only callable by Risk Engine)
+    ///
+    /// Fails if the collateral is locked for anything but the escrow backing
+    /// the liquidated position.
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to unlock
+    /// * `escrow_id` - Escrow backing the position
+    ///
+    /// # Events
+    /// Emits `CollateralUnlocked` event
+    pub fn release_collateral(env: Env, id: u64, escrow_id: u64) -> Result<(), ContractError> {
+        let risk_engine: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("risk_eng"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        risk_engine.require_auth();
+
+        let mut collateral: Collateral = env
+            .storage()
+            .persistent()
+            .get(&id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if !collateral.locked {
+            return Ok(()); // Already unlocked
+        }
+
+        Self::check_lock(&collateral, &Self::escrow_manager(&env)?, escrow_id)?;
+        Self::clear_lock(&mut collateral);
         env.storage().persistent().set(&id, &collateral);
 
         env.events().publish(
@@ -292,6 +696,18 @@ impl CollateralRegistry {
         env.storage().persistent().get(&id)
     }
 
+    /// Look up collateral by the hash of its off-chain metadata
+    ///
+    /// Lets other contracts check whether a document (e.g. an invoice) is
+    /// already registered, and whether it currently backs a deal.
+    pub fn get_collateral_by_hash(env: Env, metadata_hash: BytesN<32>) -> Option<Collateral> {
+        let collateral_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "metadata"), metadata_hash))?;
+        Self::get_collateral(env, collateral_id)
+    }
+
     /// Check if collateral is locked
     ///
     /// # Arguments
@@ -307,21 +723,116 @@ impl CollateralRegistry {
             .unwrap_or(false)
     }
 
-    /// Get admin address
-    pub fn admin(env: Env) -> Address {
-        env.storage()
-            .instance()
-            .get(&symbol_short!("admin"))
-            .unwrap()
-    }
-
-    /// Set escrow manager address (admin only)
+    /// Classify collateral by type (admin only)
+    ///
+    /// The type selects the risk parameter set the risk engine applies, so it
+    /// is assigned by the registry admin rather than the owner.
     ///
     /// # Arguments
-    /// * `escrow_manager` - Address of the escrow manager contract
-    pub fn set_escrow_manager(env: Env, escrow_manager: Address) -> Result<(), ContractError> {
-        let admin: Address = env
-            .storage()
+    /// * `id` - Collateral ID to classify
+    /// * `collateral_type` - Type symbol, e.g. `perish` or `gov_recv`
+    ///
+    /// # Events
+    /// Emits `CollateralTyped` event
+    pub fn set_collateral_type(
+        env: Env,
+        id: u64,
+        collateral_type: Symbol,
+    ) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        admin.require_auth();
+
+        if !env.storage().persistent().has(&id) {
+            return Err(ContractError::CollateralNotFound);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("coll_type"), id), &collateral_type);
+
+        env.events().publish(
+            (symbol_short!("coll_type"),),
+            (id, collateral_type),
+        );
+
+        Ok(())
+    }
+
+    /// Get the type of a collateral entry, if classified
+    pub fn get_collateral_type(env: Env, id: u64) -> Option<Symbol> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("coll_type"), id))
+    }
+
+    /// Get the face value of collateral seized by a holder
+    ///
+    /// # Arguments
+    /// * `id` - Collateral ID to query
+    /// * `holder` - Address holding seized shares
+    pub fn get_collateral_share(env: Env, id: u64, holder: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("share"), id, holder))
+            .unwrap_or(0)
+    }
+
+    /// Get the total face value seized from a collateral entry
+    pub fn get_seized_value(env: Env, id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("seized"), id))
+            .unwrap_or(0)
+    }
+
+    /// Get the eBL backing a collateral, if any
+    pub fn get_linked_ebl(env: Env, id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ebl"), id))
+    }
+
+    /// Check if collateral has been realized by settlement or surrender
+    pub fn is_realized(env: Env, id: u64) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("realized"), id))
+            .unwrap_or(false)
+    }
+
+    /// Get the face value the owner can still pledge, net of seized shares
+    ///
+    /// Realized collateral has nothing left to pledge.
+    pub fn get_pledgeable_value(env: Env, id: u64) -> i128 {
+        if Self::is_realized(env.clone(), id) {
+            return 0;
+        }
+
+        Self::get_collateral(env.clone(), id)
+            .map(|collateral| (collateral.face_value - Self::get_seized_value(env, id)).max(0))
+            .unwrap_or(0)
+    }
+
+    /// Get admin address
+    pub fn admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap()
+    }
+
+    /// Set escrow manager address (admin only)
+    ///
+    /// # Arguments
+    /// * `escrow_manager` - Address of the escrow manager contract
+    pub fn set_escrow_manager(env: Env, escrow_manager: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
             .instance()
             .get(&symbol_short!("admin"))
             .unwrap();
@@ -335,6 +846,26 @@ impl CollateralRegistry {
         Ok(())
     }
 
+    /// Set risk engine address (admin only)
+    ///
+    /// # Arguments
+    /// * `risk_engine` - Address of the risk assessment contract
+    pub fn set_risk_engine(env: Env, risk_engine: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("risk_eng"), &risk_engine);
+
+        Ok(())
+    }
+
     /// Set valuation oracle address (admin only)
     ///
     /// # Arguments
@@ -354,18 +885,107 @@ impl CollateralRegistry {
 
         Ok(())
     }
+
+    /// Set factoring manager address (admin only)
+    ///
+    /// # Arguments
+    /// * `factoring_manager` - Address of the invoice factoring contract
+    pub fn set_factoring_manager(env: Env, factoring_manager: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("fact_mgr"), &factoring_manager);
+
+        Ok(())
+    }
+
+    /// Set eBL registry address (admin only)
+    ///
+    /// # Arguments
+    /// * `ebl_registry` - Address of the eBL registry contract
+    pub fn set_ebl_registry(env: Env, ebl_registry: Address) -> Result<(), ContractError> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .unwrap();
+
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ebl_reg"), &ebl_registry);
+
+        Ok(())
+    }
+
+    fn ebl_registry(env: &Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("ebl_reg"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Fail unless `financier` holds the eBL backing the collateral, if any
+    fn check_ebl_holder(env: &Env, id: u64, financier: &Address) -> Result<(), ContractError> {
+        if let Some(ebl_id) = Self::get_linked_ebl(env.clone(), id) {
+            let holder = EblRegistryClient::new(env, &Self::ebl_registry(env)?).get_holder(&ebl_id);
+            if holder.as_ref() != Some(financier) {
+                return Err(ContractError::EblNotHeld);
+            }
+        }
+        Ok(())
+    }
+
+    fn escrow_manager(env: &Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(env, "escrow_mgr"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Fail unless `holder` locked the collateral for `reference`
+    fn check_lock(collateral: &Collateral, holder: &Address, reference: u64) -> Result<(), ContractError> {
+        if !collateral.locked {
+            return Err(ContractError::CollateralNotLocked);
+        }
+        if collateral.lock_holder.as_ref() != Some(holder) || collateral.lock_ref != reference {
+            return Err(ContractError::LockNotHeld);
+        }
+        Ok(())
+    }
+
+    fn set_lock(collateral: &mut Collateral, holder: &Address, reference: u64) {
+        collateral.locked = true;
+        collateral.lock_holder = Some(holder.clone());
+        collateral.lock_ref = reference;
+    }
+
+    fn clear_lock(collateral: &mut Collateral) {
+        collateral.locked = false;
+        collateral.lock_holder = None;
+        collateral.lock_ref = 0;
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Env};
+    use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Env};
 
     #[test]
     fn test_initialize() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             let result = CollateralRegistry::initialize(env.clone(), admin.clone());
@@ -379,9 +999,10 @@ mod test {
     #[test]
     fn test_register_collateral_success() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             // Initialize
@@ -408,17 +1029,18 @@ mod test {
             assert_eq!(collateral.owner, owner);
             assert_eq!(collateral.face_value, 1000);
             assert_eq!(collateral.realized_value, 1000);
-            assert_eq!(collateral.locked, false);
+            assert!(!collateral.locked);
         });
     }
 
     #[test]
     fn test_update_valuation_success() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let oracle = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             // Initialize
@@ -450,9 +1072,10 @@ mod test {
     #[test]
     fn test_register_collateral_invalid_amount() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
@@ -475,9 +1098,12 @@ mod test {
     #[test]
     fn test_register_collateral_expired() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        env.ledger().set_timestamp(1000);
 
         env.as_contract(&contract_id, || {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
@@ -500,10 +1126,11 @@ mod test {
     #[test]
     fn test_register_collateral_duplicate_metadata() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let owner1 = Address::generate(&env);
         let owner2 = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
@@ -536,12 +1163,13 @@ mod test {
     #[test]
     fn test_lock_unlock_collateral() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let escrow_manager = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
-        env.as_contract(&contract_id, || {
+        let collateral_id = env.as_contract(&contract_id, || {
             // Initialize
             CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
             CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager.clone()).unwrap();
@@ -549,38 +1177,124 @@ mod test {
             // Register collateral
             let future_ts = env.ledger().timestamp() + 86400;
             let metadata_hash = BytesN::from_array(&env, &[1; 32]);
-            let collateral_id = CollateralRegistry::register_collateral(
+            CollateralRegistry::register_collateral(
                 env.clone(),
                 owner,
                 1000,
                 future_ts,
                 metadata_hash,
-            ).unwrap();
+            ).unwrap()
+        });
 
-            // Lock collateral
-            let lock_result = CollateralRegistry::lock_collateral(env.clone(), collateral_id);
+        // Lock collateral (separate block to avoid auth conflict)
+        env.as_contract(&contract_id, || {
+            let lock_result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env));
             assert!(lock_result.is_ok());
             assert!(CollateralRegistry::is_locked(env.clone(), collateral_id));
+        });
 
-            // Unlock collateral
-            let unlock_result = CollateralRegistry::unlock_collateral(env.clone(), collateral_id);
+        // Unlock collateral (separate block)
+        env.as_contract(&contract_id, || {
+            let unlock_result = CollateralRegistry::unlock_collateral(env.clone(), collateral_id, 1);
             assert!(unlock_result.is_ok());
             assert!(!CollateralRegistry::is_locked(env.clone(), collateral_id));
         });
     }
 
+    #[test]
+    fn test_lock_holder_checks() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let escrow_manager = Address::generate(&env);
+        let risk_engine = Address::generate(&env);
+        let factoring_manager = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        let collateral_id = env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+            CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            CollateralRegistry::register_collateral(
+                env.clone(),
+                owner,
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap()
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::set_risk_engine(env.clone(), risk_engine.clone()).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::set_factoring_manager(env.clone(), factoring_manager.clone()).unwrap();
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env)).unwrap();
+            let collateral = CollateralRegistry::get_collateral(env.clone(), collateral_id).unwrap();
+            assert_eq!(collateral.lock_holder, Some(escrow_manager.clone()));
+            assert_eq!(collateral.lock_ref, 1);
+        });
+
+        // The risk engine closes escrow 1's position and the owner pledges again
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::release_collateral(env.clone(), collateral_id, 1).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 2, Address::generate(&env)).unwrap();
+        });
+
+        // Escrow 1 winding down cannot free escrow 2's lock, nor can the risk
+        // engine for escrow 1's position or the factoring manager
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::unlock_collateral(env.clone(), collateral_id, 1);
+            assert_eq!(result, Err(ContractError::LockNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::release_collateral(env.clone(), collateral_id, 1);
+            assert_eq!(result, Err(ContractError::LockNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::seize_collateral(env.clone(), collateral_id, 1, admin.clone(), 100);
+            assert_eq!(result, Err(ContractError::LockNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::release_assignment(env.clone(), collateral_id, 2);
+            assert_eq!(result, Err(ContractError::LockNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::transfer_collateral(env.clone(), collateral_id, 1, admin.clone());
+            assert_eq!(result, Err(ContractError::LockNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            assert!(CollateralRegistry::is_locked(env.clone(), collateral_id));
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::unlock_collateral(env.clone(), collateral_id, 2).unwrap();
+            let collateral = CollateralRegistry::get_collateral(env.clone(), collateral_id).unwrap();
+            assert!(!collateral.locked);
+            assert_eq!(collateral.lock_holder, None);
+        });
+    }
+
     #[test]
     fn test_lock_collateral_not_found() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
         let escrow_manager = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
             CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager).unwrap();
 
-            let result = CollateralRegistry::lock_collateral(env.clone(), 999);
+            let result = CollateralRegistry::lock_collateral(env.clone(), 999, 1, Address::generate(&env));
             assert_eq!(result, Err(ContractError::CollateralNotFound));
         });
     }
@@ -588,10 +1302,10 @@ mod test {
     #[test]
     fn test_lock_collateral_unauthorized() {
         let env = Env::default();
+        env.mock_all_auths();
         let admin = Address::generate(&env);
-        let unauthorized = Address::generate(&env);
         let owner = Address::generate(&env);
-        let contract_id = env.register_contract(None, CollateralRegistry);
+        let contract_id = env.register(CollateralRegistry, ());
 
         env.as_contract(&contract_id, || {
             CollateralRegistry::initialize(env.clone(), admin).unwrap();
@@ -608,8 +1322,332 @@ mod test {
             ).unwrap();
 
             // Try to lock with unauthorized address (no escrow manager set)
-            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id);
+            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env));
             assert_eq!(result, Err(ContractError::Unauthorized));
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_seize_and_release_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let escrow_manager = Address::generate(&env);
+        let risk_engine = Address::generate(&env);
+        let liquidator = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        let collateral_id = env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+            CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            CollateralRegistry::register_collateral(
+                env.clone(),
+                owner,
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap()
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::set_risk_engine(env.clone(), risk_engine.clone()).unwrap();
+        });
+
+        // Unlocked collateral cannot be seized
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::seize_collateral(env.clone(), collateral_id, 1, liquidator.clone(), 400);
+            assert_eq!(result, Err(ContractError::CollateralNotLocked));
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env)).unwrap();
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::seize_collateral(env.clone(), collateral_id, 1, liquidator.clone(), 400).unwrap();
+            assert_eq!(CollateralRegistry::get_collateral_share(env.clone(), collateral_id, liquidator.clone()), 400);
+            assert_eq!(CollateralRegistry::get_seized_value(env.clone(), collateral_id), 400);
+        });
+
+        // Cannot seize more than the remaining face value
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::seize_collateral(env.clone(), collateral_id, 1, liquidator.clone(), 601);
+            assert_eq!(result, Err(ContractError::ExceedsCollateralValue));
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::release_collateral(env.clone(), collateral_id, 1).unwrap();
+            assert!(!CollateralRegistry::is_locked(env.clone(), collateral_id));
+            assert_eq!(CollateralRegistry::get_pledgeable_value(env.clone(), collateral_id), 600);
+        });
+
+        // Fully seized collateral cannot be pledged again
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env)).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::seize_collateral(env.clone(), collateral_id, 1, liquidator.clone(), 600).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::release_collateral(env.clone(), collateral_id, 1).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env));
+            assert_eq!(result, Err(ContractError::CollateralFullySeized));
+        });
+    }
+
+    #[test]
+    fn test_transfer_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let escrow_manager = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let new_owner = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        let collateral_id = env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+            CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            CollateralRegistry::register_collateral(
+                env.clone(),
+                owner.clone(),
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap()
+        });
+
+        // Only locked collateral can change hands
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::transfer_collateral(env.clone(), collateral_id, 1, new_owner.clone());
+            assert_eq!(result, Err(ContractError::CollateralNotLocked));
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, Address::generate(&env)).unwrap();
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::transfer_collateral(env.clone(), collateral_id, 1, new_owner.clone()).unwrap();
+            let collateral = CollateralRegistry::get_collateral(env.clone(), collateral_id).unwrap();
+            assert_eq!(collateral.owner, new_owner);
+            assert!(collateral.locked);
+        });
+    }
+
+    #[test]
+    fn test_assign_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let factoring_manager = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let factor = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        let collateral_id = env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+            CollateralRegistry::set_factoring_manager(env.clone(), factoring_manager.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            CollateralRegistry::register_collateral(
+                env.clone(),
+                seller.clone(),
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap()
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::assign_collateral(env.clone(), collateral_id, 1, factor.clone()).unwrap();
+            let collateral = CollateralRegistry::get_collateral(env.clone(), collateral_id).unwrap();
+            assert_eq!(collateral.owner, factor);
+            assert!(collateral.locked);
+        });
+
+        // An assigned invoice cannot be assigned again
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::assign_collateral(env.clone(), collateral_id, 1, seller.clone());
+            assert_eq!(result, Err(ContractError::CollateralLocked));
+        });
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::release_assignment(env.clone(), collateral_id, 1).unwrap();
+            assert!(!CollateralRegistry::is_locked(env.clone(), collateral_id));
+        });
+
+        // Once the invoice is paid it is realized and cannot be pledged again
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::assign_collateral(env.clone(), collateral_id, 1, factor.clone()).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::settle_assignment(env.clone(), collateral_id, 1).unwrap();
+            assert!(!CollateralRegistry::is_locked(env.clone(), collateral_id));
+            assert!(CollateralRegistry::is_realized(env.clone(), collateral_id));
+            assert_eq!(CollateralRegistry::get_pledgeable_value(env.clone(), collateral_id), 0);
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::assign_collateral(env.clone(), collateral_id, 1, seller.clone());
+            assert_eq!(result, Err(ContractError::CollateralRealized));
+        });
+    }
+
+    /// eBL registry stand-in reporting a settable holder
+    #[contract]
+    pub struct MockEblRegistry;
+
+    #[contractimpl]
+    impl MockEblRegistry {
+        pub fn set_holder(env: Env, ebl_id: u64, holder: Address) {
+            env.storage().instance().set(&ebl_id, &holder);
+        }
+
+        pub fn get_holder(env: Env, ebl_id: u64) -> Option<Address> {
+            env.storage().instance().get(&ebl_id)
+        }
+    }
+
+    #[test]
+    fn test_ebl_backed_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let escrow_manager = Address::generate(&env);
+        let factoring_manager = Address::generate(&env);
+        let shipper = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+        let ebl_registry = env.register(MockEblRegistry, ());
+        let ebls = MockEblRegistryClient::new(&env, &ebl_registry);
+
+        let collateral_id = env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+            CollateralRegistry::set_escrow_manager(env.clone(), escrow_manager.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            CollateralRegistry::register_collateral(
+                env.clone(),
+                shipper.clone(),
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap()
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::set_factoring_manager(env.clone(), factoring_manager.clone()).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::set_ebl_registry(env.clone(), ebl_registry.clone()).unwrap();
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::link_ebl(env.clone(), collateral_id, 7).unwrap();
+            assert_eq!(CollateralRegistry::get_linked_ebl(env.clone(), collateral_id), Some(7));
+        });
+
+        // Only the party holding the eBL can finance the goods
+        ebls.set_holder(&7, &shipper);
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, lender.clone());
+            assert_eq!(result, Err(ContractError::EblNotHeld));
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::assign_collateral(env.clone(), collateral_id, 1, lender.clone());
+            assert_eq!(result, Err(ContractError::EblNotHeld));
+        });
+
+        ebls.set_holder(&7, &lender);
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::lock_collateral(env.clone(), collateral_id, 1, lender.clone()).unwrap();
+        });
+
+        // Goods still financed cannot be released to the consignee
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::realize_collateral(env.clone(), collateral_id);
+            assert_eq!(result, Err(ContractError::CollateralLocked));
+        });
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::unlock_collateral(env.clone(), collateral_id, 1).unwrap();
+        });
+
+        // Once the eBL is surrendered the collateral is realized
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::realize_collateral(env.clone(), collateral_id).unwrap();
+            assert!(CollateralRegistry::is_realized(env.clone(), collateral_id));
+            assert_eq!(CollateralRegistry::get_pledgeable_value(env.clone(), collateral_id), 0);
+        });
+        env.as_contract(&contract_id, || {
+            let result = CollateralRegistry::lock_collateral(env.clone(), collateral_id, 2, lender.clone());
+            assert_eq!(result, Err(ContractError::CollateralRealized));
+        });
+    }
+
+    #[test]
+    fn test_get_collateral_by_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[7; 32]);
+            let collateral_id = CollateralRegistry::register_collateral(
+                env.clone(),
+                owner.clone(),
+                1000,
+                future_ts,
+                metadata_hash.clone(),
+            ).unwrap();
+
+            let collateral = CollateralRegistry::get_collateral_by_hash(env.clone(), metadata_hash).unwrap();
+            assert_eq!(collateral.id, collateral_id);
+            assert!(CollateralRegistry::get_collateral_by_hash(env.clone(), BytesN::from_array(&env, &[8; 32])).is_none());
+        });
+    }
+
+    #[test]
+    fn test_set_collateral_type() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let owner = Address::generate(&env);
+        let contract_id = env.register(CollateralRegistry, ());
+
+        env.as_contract(&contract_id, || {
+            CollateralRegistry::initialize(env.clone(), admin.clone()).unwrap();
+
+            let future_ts = env.ledger().timestamp() + 86400;
+            let metadata_hash = BytesN::from_array(&env, &[1; 32]);
+            let collateral_id = CollateralRegistry::register_collateral(
+                env.clone(),
+                owner,
+                1000,
+                future_ts,
+                metadata_hash,
+            ).unwrap();
+
+            assert_eq!(CollateralRegistry::get_collateral_type(env.clone(), collateral_id), None);
+
+            CollateralRegistry::set_collateral_type(env.clone(), collateral_id, symbol_short!("perish")).unwrap();
+            assert_eq!(
+                CollateralRegistry::get_collateral_type(env.clone(), collateral_id),
+                Some(symbol_short!("perish"))
+            );
+        });
+    }
+}
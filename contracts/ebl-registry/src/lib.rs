@@ -0,0 +1,746 @@
+//! Electronic Bill of Lading Registry for StelloVault
+//!
+//! This contract registers electronic bills of lading (eBLs) by document hash
+//! and tracks who controls each one. Following the MLETR model, exclusive
+//! control of the eBL record stands in for possession of the paper original:
+//! title passes by endorsement from the current holder, every change of
+//! control is kept in an append-only history, and the document is surrendered
+//! to the carrier on delivery. An eBL can be linked to CollateralRegistry
+//! collateral so that a lender who holds the eBL holds the goods: the
+//! registry only locks linked collateral for the party holding the eBL, and
+//! marks it realized once the eBL is surrendered.
+
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Vec,
+};
+
+/// Contract errors
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Unauthorized = 1,
+    AlreadyInitialized = 2,
+    EblNotFound = 3,
+    DuplicateDocument = 4,
+    NotCarrier = 5,
+    InvalidStatus = 6,
+    InvalidEndorsee = 7,
+    RegistryNotConfigured = 8,
+    CollateralNotFound = 9,
+    CollateralMismatch = 10,
+    CollateralLocked = 11,
+    AlreadyLinked = 12,
+}
+
+impl From<soroban_sdk::Error> for ContractError {
+    fn from(_: soroban_sdk::Error) -> Self {
+        ContractError::Unauthorized
+    }
+}
+
+impl From<&ContractError> for soroban_sdk::Error {
+    fn from(err: &ContractError) -> Self {
+        soroban_sdk::Error::from_contract_error(*err as u32)
+    }
+}
+
+/// eBL lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EblStatus {
+    Active = 0,
+    Surrendered = 1,
+}
+
+/// How control of an eBL changed hands
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransferKind {
+    /// Carrier issued the eBL to the shipper
+    Issue = 0,
+    /// Holder endorsed the eBL to a new holder
+    Endorsement = 1,
+    /// Holder surrendered the eBL to the carrier against delivery
+    Surrender = 2,
+}
+
+/// One entry in an eBL's title history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TitleTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub kind: TransferKind,
+    pub timestamp: u64,
+}
+
+/// Electronic bill of lading data structure
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BillOfLading {
+    pub id: u64,
+    pub document_hash: BytesN<32>,
+    pub carrier: Address,
+    pub shipper: Address,
+    pub holder: Address,
+    pub status: EblStatus,
+    pub issued_at: u64,
+    pub surrendered_at: u64, // Set on surrender
+}
+
+/// Registry collateral (mirrors CollateralRegistry)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Collateral {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub lock_holder: Option<Address>,
+    pub lock_ref: u64,
+}
+
+/// CollateralRegistry interface
+#[contractclient(name = "CollateralRegistryClient")]
+pub trait CollateralRegistry {
+    fn get_collateral(env: Env, id: u64) -> Option<Collateral>;
+    fn link_ebl(env: Env, id: u64, ebl_id: u64);
+    fn realize_collateral(env: Env, id: u64);
+}
+
+/// Maximum number of history entries returned per page
+const MAX_PAGE_SIZE: u32 = 50;
+
+#[contract]
+pub struct EblRegistry;
+
+#[contractimpl]
+impl EblRegistry {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> Result<(), ContractError> {
+        if env.storage().instance().has(&symbol_short!("admin")) {
+            return Err(ContractError::AlreadyInitialized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ebl_next"), &1u64);
+
+        env.events().publish((symbol_short!("ebl_init"),), (admin,));
+        Ok(())
+    }
+
+    /// Allow a carrier to issue eBLs (admin only)
+    ///
+    /// # Events
+    /// Emits `carr_add` with the carrier address
+    pub fn add_carrier(env: Env, carrier: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("carrier"), carrier.clone()), &true);
+
+        env.events()
+            .publish((symbol_short!("carr_add"),), (carrier,));
+        Ok(())
+    }
+
+    /// Stop a carrier from issuing new eBLs (admin only)
+    ///
+    /// eBLs the carrier already issued remain valid and can still be
+    /// endorsed and surrendered.
+    ///
+    /// # Events
+    /// Emits `carr_rem` with the carrier address
+    pub fn remove_carrier(env: Env, carrier: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("carrier"), carrier.clone()));
+
+        env.events()
+            .publish((symbol_short!("carr_rem"),), (carrier,));
+        Ok(())
+    }
+
+    /// Issue an eBL to the shipper (carrier only)
+    ///
+    /// Each document hash can be registered once, so there is never more
+    /// than one controllable record for the same bill of lading.
+    ///
+    /// # Arguments
+    /// * `carrier` - Whitelisted carrier issuing the eBL
+    /// * `document_hash` - Hash of the off-chain bill of lading
+    /// * `shipper` - Initial holder
+    ///
+    /// # Events
+    /// Emits `ebl_iss` with (ebl_id, carrier, shipper)
+    pub fn issue_ebl(
+        env: Env,
+        carrier: Address,
+        document_hash: BytesN<32>,
+        shipper: Address,
+    ) -> Result<u64, ContractError> {
+        carrier.require_auth();
+
+        if !Self::is_carrier(env.clone(), carrier.clone()) {
+            return Err(ContractError::NotCarrier);
+        }
+
+        let hash_key = (symbol_short!("doc_hash"), document_hash.clone());
+        if env.storage().persistent().has(&hash_key) {
+            return Err(ContractError::DuplicateDocument);
+        }
+
+        let ebl_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("ebl_next"))
+            .unwrap_or(1);
+
+        let ebl = BillOfLading {
+            id: ebl_id,
+            document_hash,
+            carrier: carrier.clone(),
+            shipper: shipper.clone(),
+            holder: shipper.clone(),
+            status: EblStatus::Active,
+            issued_at: env.ledger().timestamp(),
+            surrendered_at: 0,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ebl"), ebl_id), &ebl);
+        env.storage().persistent().set(&hash_key, &ebl_id);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("ebl_next"), &(ebl_id + 1));
+
+        Self::record_transfer(&env, ebl_id, &carrier, &shipper, TransferKind::Issue);
+
+        env.events()
+            .publish((symbol_short!("ebl_iss"),), (ebl_id, carrier, shipper));
+
+        Ok(ebl_id)
+    }
+
+    /// Endorse an eBL to a new holder (current holder only)
+    ///
+    /// A borrower pledging the goods endorses the eBL to the lender before
+    /// the financing locks the collateral; the lender endorses it onward to
+    /// a buyer or back to the borrower once the financing is settled. An
+    /// eBL whose linked collateral is locked cannot change hands.
+    ///
+    /// # Events
+    /// Emits `ebl_end` with (ebl_id, from, to)
+    pub fn endorse(env: Env, ebl_id: u64, endorsee: Address) -> Result<(), ContractError> {
+        let mut ebl = Self::load_ebl(&env, ebl_id)?;
+
+        ebl.holder.require_auth();
+
+        if ebl.status != EblStatus::Active {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if endorsee == ebl.holder {
+            return Err(ContractError::InvalidEndorsee);
+        }
+
+        Self::ensure_collateral_unlocked(&env, ebl_id)?;
+
+        let from = ebl.holder.clone();
+        ebl.holder = endorsee.clone();
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ebl"), ebl_id), &ebl);
+
+        Self::record_transfer(&env, ebl_id, &from, &endorsee, TransferKind::Endorsement);
+
+        env.events()
+            .publish((symbol_short!("ebl_end"),), (ebl_id, from, endorsee));
+        Ok(())
+    }
+
+    /// Surrender an eBL to the carrier against delivery of the goods
+    ///
+    /// Both the holder and the carrier must authorize, the carrier
+    /// acknowledging delivery. An eBL whose linked collateral is still
+    /// locked in a financing cannot be surrendered. Surrendered eBLs are
+    /// final and can no longer be endorsed, and their linked collateral is
+    /// marked realized so the delivered goods cannot be pledged again.
+    ///
+    /// # Events
+    /// Emits `ebl_sur` with (ebl_id, holder)
+    pub fn surrender(env: Env, ebl_id: u64) -> Result<(), ContractError> {
+        let mut ebl = Self::load_ebl(&env, ebl_id)?;
+
+        ebl.holder.require_auth();
+        ebl.carrier.require_auth();
+
+        if ebl.status != EblStatus::Active {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        Self::ensure_collateral_unlocked(&env, ebl_id)?;
+        if let Some(collateral_id) = Self::get_linked_collateral(env.clone(), ebl_id) {
+            Self::registry_client(&env)?.realize_collateral(&collateral_id);
+        }
+
+        let holder = ebl.holder.clone();
+        ebl.holder = ebl.carrier.clone();
+        ebl.status = EblStatus::Surrendered;
+        ebl.surrendered_at = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ebl"), ebl_id), &ebl);
+
+        Self::record_transfer(&env, ebl_id, &holder, &ebl.carrier, TransferKind::Surrender);
+
+        env.events()
+            .publish((symbol_short!("ebl_sur"),), (ebl_id, holder));
+        Ok(())
+    }
+
+    /// Link an eBL to CollateralRegistry collateral (current holder only)
+    ///
+    /// The collateral must have been registered with the eBL's document hash
+    /// as its metadata hash and be owned by the current holder. A link is
+    /// permanent for the life of the eBL, and from then on the registry only
+    /// locks the collateral for a financing party holding the eBL, so the
+    /// borrower endorses the eBL to the lender before the deal opens.
+    ///
+    /// # Events
+    /// Emits `ebl_link` with (ebl_id, collateral_id)
+    pub fn link_collateral(env: Env, ebl_id: u64, collateral_id: u64) -> Result<(), ContractError> {
+        let ebl = Self::load_ebl(&env, ebl_id)?;
+
+        ebl.holder.require_auth();
+
+        if ebl.status != EblStatus::Active {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if Self::get_linked_collateral(env.clone(), ebl_id).is_some() {
+            return Err(ContractError::AlreadyLinked);
+        }
+
+        let collateral = Self::registry_client(&env)?
+            .get_collateral(&collateral_id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.metadata_hash != ebl.document_hash || collateral.owner != ebl.holder {
+            return Err(ContractError::CollateralMismatch);
+        }
+
+        // Collateral already financed was locked without checking who
+        // holds the eBL
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+
+        Self::registry_client(&env)?.link_ebl(&collateral_id, &ebl_id);
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("ebl_coll"), ebl_id), &collateral_id);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("coll_ebl"), collateral_id), &ebl_id);
+
+        env.events()
+            .publish((symbol_short!("ebl_link"),), (ebl_id, collateral_id));
+        Ok(())
+    }
+
+    /// Set the collateral registry eBLs link to (admin only)
+    pub fn set_collateral_registry(env: Env, registry: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("coll_reg"), &registry);
+        Ok(())
+    }
+
+    /// Get contract admin
+    pub fn admin(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Check whether an address may issue eBLs
+    pub fn is_carrier(env: Env, carrier: Address) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("carrier"), carrier))
+            .unwrap_or(false)
+    }
+
+    /// Get an eBL
+    pub fn get_ebl(env: Env, ebl_id: u64) -> Option<BillOfLading> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ebl"), ebl_id))
+    }
+
+    /// Look up an eBL by the hash of its document
+    pub fn get_ebl_by_hash(env: Env, document_hash: BytesN<32>) -> Option<BillOfLading> {
+        let ebl_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("doc_hash"), document_hash))?;
+        Self::get_ebl(env, ebl_id)
+    }
+
+    /// Get the current holder of an eBL
+    pub fn get_holder(env: Env, ebl_id: u64) -> Option<Address> {
+        Self::get_ebl(env, ebl_id).map(|ebl| ebl.holder)
+    }
+
+    /// Get the collateral ID an eBL is linked to, if any
+    pub fn get_linked_collateral(env: Env, ebl_id: u64) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ebl_coll"), ebl_id))
+    }
+
+    /// Get the eBL backing a collateral ID, if any
+    ///
+    /// Lenders use this to confirm they hold title to the goods behind
+    /// the collateral they finance.
+    pub fn get_ebl_by_collateral(env: Env, collateral_id: u64) -> Option<BillOfLading> {
+        let ebl_id: u64 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("coll_ebl"), collateral_id))?;
+        Self::get_ebl(env, ebl_id)
+    }
+
+    /// Get the number of title history entries for an eBL
+    pub fn get_history_count(env: Env, ebl_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("hist_cnt"), ebl_id))
+            .unwrap_or(0)
+    }
+
+    /// Get a page of an eBL's title history, oldest first
+    ///
+    /// # Arguments
+    /// * `ebl_id` - The eBL ID
+    /// * `start` - Index of the first entry
+    /// * `limit` - Maximum number of entries (capped at 50)
+    pub fn get_history(env: Env, ebl_id: u64, start: u32, limit: u32) -> Vec<TitleTransfer> {
+        let mut history = Vec::new(&env);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::get_history_count(env.clone(), ebl_id));
+
+        for index in start..end {
+            if let Some(entry) =
+                env.storage()
+                    .persistent()
+                    .get(&(symbol_short!("hist"), ebl_id, index))
+            {
+                history.push_back(entry);
+            }
+        }
+
+        history
+    }
+
+    fn load_ebl(env: &Env, ebl_id: u64) -> Result<BillOfLading, ContractError> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ebl"), ebl_id))
+            .ok_or(ContractError::EblNotFound)
+    }
+
+    /// Fail if the eBL's linked collateral is locked in a financing
+    fn ensure_collateral_unlocked(env: &Env, ebl_id: u64) -> Result<(), ContractError> {
+        if let Some(collateral_id) = Self::get_linked_collateral(env.clone(), ebl_id) {
+            let collateral = Self::registry_client(env)?
+                .get_collateral(&collateral_id)
+                .ok_or(ContractError::CollateralNotFound)?;
+            if collateral.locked {
+                return Err(ContractError::CollateralLocked);
+            }
+        }
+        Ok(())
+    }
+
+    fn registry_client(env: &Env) -> Result<CollateralRegistryClient<'_>, ContractError> {
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::RegistryNotConfigured)?;
+        Ok(CollateralRegistryClient::new(env, &registry))
+    }
+
+    /// Append an entry to an eBL's title history
+    fn record_transfer(env: &Env, ebl_id: u64, from: &Address, to: &Address, kind: TransferKind) {
+        let index = Self::get_history_count(env.clone(), ebl_id);
+        let entry = TitleTransfer {
+            from: from.clone(),
+            to: to.clone(),
+            kind,
+            timestamp: env.ledger().timestamp(),
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("hist"), ebl_id, index), &entry);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("hist_cnt"), ebl_id), &(index + 1));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use collateral_registry::{
+        CollateralRegistry as Registry, CollateralRegistryClient as RegistryClient,
+    };
+    use soroban_sdk::testutils::Address as _;
+
+    struct Setup<'a> {
+        env: Env,
+        client: EblRegistryClient<'a>,
+        contract_id: Address,
+        carrier: Address,
+        shipper: Address,
+    }
+
+    fn setup() -> Setup<'static> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(EblRegistry, ());
+        let client = EblRegistryClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+
+        let carrier = Address::generate(&env);
+        client.add_carrier(&carrier);
+
+        Setup {
+            client,
+            contract_id,
+            carrier,
+            shipper: Address::generate(&env),
+            env,
+        }
+    }
+
+    fn document_hash(env: &Env, seed: u8) -> BytesN<32> {
+        BytesN::from_array(env, &[seed; 32])
+    }
+
+    #[test]
+    fn test_initialize() {
+        let s = setup();
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::initialize(s.env.clone(), Address::generate(&s.env))
+        });
+        assert_eq!(result, Err(ContractError::AlreadyInitialized));
+    }
+
+    #[test]
+    fn test_issue_requires_carrier_and_unique_hash() {
+        let s = setup();
+        let hash = document_hash(&s.env, 1);
+
+        let stranger = Address::generate(&s.env);
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::issue_ebl(
+                s.env.clone(),
+                stranger.clone(),
+                hash.clone(),
+                s.shipper.clone(),
+            )
+        });
+        assert_eq!(result, Err(ContractError::NotCarrier));
+
+        let ebl_id = s.client.issue_ebl(&s.carrier, &hash, &s.shipper);
+        assert_eq!(ebl_id, 1);
+
+        let ebl = s.client.get_ebl_by_hash(&hash).unwrap();
+        assert_eq!(ebl.holder, s.shipper);
+        assert_eq!(ebl.status, EblStatus::Active);
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::issue_ebl(
+                s.env.clone(),
+                s.carrier.clone(),
+                hash.clone(),
+                s.shipper.clone(),
+            )
+        });
+        assert_eq!(result, Err(ContractError::DuplicateDocument));
+
+        // Removed carriers cannot issue further eBLs
+        s.client.remove_carrier(&s.carrier);
+        assert!(!s.client.is_carrier(&s.carrier));
+        assert!(s
+            .client
+            .try_issue_ebl(&s.carrier, &document_hash(&s.env, 2), &s.shipper)
+            .is_err());
+    }
+
+    #[test]
+    fn test_endorsement_history() {
+        let s = setup();
+        let ebl_id = s
+            .client
+            .issue_ebl(&s.carrier, &document_hash(&s.env, 1), &s.shipper);
+
+        let bank = Address::generate(&s.env);
+        let buyer = Address::generate(&s.env);
+        s.client.endorse(&ebl_id, &bank);
+        s.client.endorse(&ebl_id, &buyer);
+        assert_eq!(s.client.get_holder(&ebl_id), Some(buyer.clone()));
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::endorse(s.env.clone(), ebl_id, buyer.clone())
+        });
+        assert_eq!(result, Err(ContractError::InvalidEndorsee));
+
+        assert_eq!(s.client.get_history_count(&ebl_id), 3);
+        let history = s.client.get_history(&ebl_id, &0, &10);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.get(0).unwrap().kind, TransferKind::Issue);
+        assert_eq!(history.get(0).unwrap().from, s.carrier);
+        assert_eq!(history.get(1).unwrap().to, bank);
+        assert_eq!(history.get(2).unwrap().from, bank);
+        assert_eq!(history.get(2).unwrap().to, buyer);
+
+        let page = s.client.get_history(&ebl_id, &2, &10);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page.get(0).unwrap().kind, TransferKind::Endorsement);
+    }
+
+    #[test]
+    fn test_surrender_is_final() {
+        let s = setup();
+        let ebl_id = s
+            .client
+            .issue_ebl(&s.carrier, &document_hash(&s.env, 1), &s.shipper);
+
+        let consignee = Address::generate(&s.env);
+        s.client.endorse(&ebl_id, &consignee);
+        s.client.surrender(&ebl_id);
+
+        let ebl = s.client.get_ebl(&ebl_id).unwrap();
+        assert_eq!(ebl.status, EblStatus::Surrendered);
+        assert_eq!(ebl.holder, s.carrier);
+
+        let history = s.client.get_history(&ebl_id, &0, &10);
+        let last = history.get(history.len() - 1).unwrap();
+        assert_eq!(last.kind, TransferKind::Surrender);
+        assert_eq!(last.from, consignee);
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::endorse(s.env.clone(), ebl_id, Address::generate(&s.env))
+        });
+        assert_eq!(result, Err(ContractError::InvalidStatus));
+    }
+
+    #[test]
+    fn test_collateral_link_and_surrender_lock() {
+        let s = setup();
+        let hash = document_hash(&s.env, 1);
+        let ebl_id = s.client.issue_ebl(&s.carrier, &hash, &s.shipper);
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::link_collateral(s.env.clone(), ebl_id, 1)
+        });
+        assert_eq!(result, Err(ContractError::RegistryNotConfigured));
+
+        let registry_id = s.env.register(Registry, ());
+        let registry = RegistryClient::new(&s.env, &registry_id);
+        let escrow_manager = Address::generate(&s.env);
+        registry.initialize(&Address::generate(&s.env));
+        registry.set_escrow_manager(&escrow_manager);
+        registry.set_ebl_registry(&s.contract_id);
+        s.client.set_collateral_registry(&registry_id);
+
+        // Collateral registered for a different document
+        let expiry = s.env.ledger().timestamp() + 86400;
+        let other =
+            registry.register_collateral(&s.shipper, &1000, &expiry, &document_hash(&s.env, 9));
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::link_collateral(s.env.clone(), ebl_id, other)
+        });
+        assert_eq!(result, Err(ContractError::CollateralMismatch));
+
+        let collateral_id = registry.register_collateral(&s.shipper, &1000, &expiry, &hash);
+        s.client.link_collateral(&ebl_id, &collateral_id);
+        assert_eq!(s.client.get_linked_collateral(&ebl_id), Some(collateral_id));
+        assert_eq!(
+            s.client.get_ebl_by_collateral(&collateral_id).unwrap().id,
+            ebl_id
+        );
+        assert_eq!(registry.get_linked_ebl(&collateral_id), Some(ebl_id));
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::link_collateral(s.env.clone(), ebl_id, collateral_id)
+        });
+        assert_eq!(result, Err(ContractError::AlreadyLinked));
+
+        // The lender can't finance the goods until the borrower pledges the eBL
+        let lender = Address::generate(&s.env);
+        assert!(registry
+            .try_lock_collateral(&collateral_id, &1, &lender)
+            .is_err());
+        s.client.endorse(&ebl_id, &lender);
+        assert_eq!(
+            s.client
+                .get_ebl_by_collateral(&collateral_id)
+                .unwrap()
+                .holder,
+            lender
+        );
+        registry.lock_collateral(&collateral_id, &1, &lender);
+
+        // Neither endorsement nor surrender can move the goods while financed
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::endorse(s.env.clone(), ebl_id, Address::generate(&s.env))
+        });
+        assert_eq!(result, Err(ContractError::CollateralLocked));
+        let result = s.env.as_contract(&s.contract_id, || {
+            EblRegistry::surrender(s.env.clone(), ebl_id)
+        });
+        assert_eq!(result, Err(ContractError::CollateralLocked));
+
+        // Financing settled, goods can be released and the collateral is spent
+        registry.unlock_collateral(&collateral_id, &1);
+        s.client.surrender(&ebl_id);
+        assert_eq!(
+            s.client.get_ebl(&ebl_id).unwrap().status,
+            EblStatus::Surrendered
+        );
+        assert!(registry.is_realized(&collateral_id));
+        assert!(registry
+            .try_lock_collateral(&collateral_id, &2, &s.carrier)
+            .is_err());
+    }
+}
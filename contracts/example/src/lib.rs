@@ -7,7 +7,8 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, Symbol,
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, Symbol, Vec,
 };
 
 /// Contract errors
@@ -35,6 +36,21 @@ pub enum ContractError {
     VoteOverflow = 19,
     VotePeriodActive = 20,
     QuorumNotMet = 21,
+    EvidenceWindowClosed = 22,
+    EvidenceWindowOpen = 23,
+    InvalidRuling = 24,
+    InvalidMilestones = 25,
+    MilestoneOutOfOrder = 26,
+    InvalidApprovalPolicy = 27,
+    ApprovalPolicyNotFound = 28,
+    AlreadyApproved = 29,
+    RegistryNotConfigured = 30,
+    CollateralExpired = 31,
+    SwapAdapterNotConfigured = 32,
+    SlippageExceeded = 33,
+    InvalidFeeConfig = 34,
+    RiskEngineNotConfigured = 35,
+    LoanManagementNotConfigured = 36,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -49,27 +65,117 @@ impl From<&ContractError> for soroban_sdk::Error {
     }
 }
 
+/// Registry collateral (mirrors CollateralRegistry)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Collateral {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub lock_holder: Option<Address>,
+    pub lock_ref: u64,
+}
+
 /// Collateral Registry Interface
-pub trait CollateralRegistryClient {
-    fn lock_collateral(env: &Env, collateral_id: u64);
-    fn unlock_collateral(env: &Env, collateral_id: u64);
+///
+/// The vault must be registered as the registry's escrow manager.
+#[contractclient(name = "CollateralRegistryClient")]
+pub trait CollateralRegistry {
+    fn get_collateral(env: Env, id: u64) -> Option<Collateral>;
+    fn get_collateral_type(env: Env, id: u64) -> Option<Symbol>;
+    fn get_pledgeable_value(env: Env, id: u64) -> i128;
+    fn lock_collateral(env: Env, id: u64, escrow_id: u64, lender: Address);
+    fn unlock_collateral(env: Env, id: u64, escrow_id: u64);
 }
 
 /// Oracle Adapter Interface
-pub trait OracleAdapterClient {
-    fn verify_release_condition(env: &Env, metadata: Symbol) -> bool;
+#[contractclient(name = "OracleAdapterClient")]
+pub trait OracleAdapter {
+    fn register_release_condition(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        escrow_ref: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+    );
+    fn register_milestone_condition(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        escrow_ref: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+    );
 }
 
-/// Collateral token data structure
+/// Risk parameters (mirrors RiskAssessment)
 #[contracttype]
-#[derive(Clone)]
-pub struct CollateralToken {
-    pub owner: Address,
-    pub asset_type: Symbol,
-    pub asset_value: i128,
-    pub metadata: Symbol,
-    pub fractional_shares: u32,
-    pub created_at: u64,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskParameters {
+    pub liquidation_threshold: u32,
+    pub liquidation_penalty: u32,
+    pub min_health_factor: u32,
+    pub max_liquidation_ratio: u32,
+    pub grace_period: u64,
+    pub liquidator_bonus: u32,
+}
+
+/// Protocol fee configuration
+///
+/// The vault takes `escrow_release_bps` of every release to the seller.
+/// LoanManagement charges `origination_bps` on new loans and RiskAssessment
+/// pays `liquidation_share_bps` of each liquidation penalty to the treasury.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub origination_bps: u32,
+    pub escrow_release_bps: u32,
+    pub liquidation_share_bps: u32, // Share of the liquidation penalty
+    pub treasury: Address,
+}
+
+/// Loan Management Interface
+///
+/// The vault must be LoanManagement's governance for fee updates to apply.
+#[contractclient(name = "LoanManagementClient")]
+pub trait LoanManagement {
+    fn update_fee_config(env: Env, config: FeeConfig);
+}
+
+/// Risk Assessment Interface
+///
+/// The vault must be registered as the risk engine's governance address.
+/// Updates land as timelocked pending updates on the risk engine.
+#[contractclient(name = "RiskAssessmentClient")]
+pub trait RiskAssessment {
+    fn update_risk_parameters(env: Env, new_params: RiskParameters);
+    fn update_type_risk_parameters(env: Env, collateral_type: Symbol, new_params: RiskParameters);
+    fn update_fee_config(env: Env, config: FeeConfig);
+}
+
+/// Swap Adapter Interface
+///
+/// The vault transfers `amount_in` of `asset_in` to the adapter before
+/// calling `swap`. The adapter must deliver at least `min_out` of
+/// `asset_out` to `to` and return the amount delivered, or fail. The vault
+/// checks the recipient's balance rather than trusting the returned amount.
+#[contractclient(name = "SwapAdapterClient")]
+pub trait SwapAdapter {
+    fn swap(
+        env: Env,
+        asset_in: Address,
+        amount_in: i128,
+        asset_out: Address,
+        min_out: i128,
+        to: Address,
+    ) -> i128;
 }
 
 /// Escrow data structure for trade finance deals
@@ -79,7 +185,7 @@ pub struct TradeEscrow {
     pub buyer: Address,
     pub seller: Address,
     pub lender: Address, // New: Lender involved in the deal
-    pub collateral_token_id: u64,
+    pub collateral_token_id: u64, // Collateral ID in the CollateralRegistry
     pub amount: i128,
     pub asset: Address, // New: Payment asset
     pub status: EscrowStatus,
@@ -87,17 +193,52 @@ pub struct TradeEscrow {
     pub release_conditions: Symbol,
     pub expiry_ts: u64, // New: Expiration timestamp
     pub created_at: u64,
+    pub released_amount: i128, // Paid out to the seller so far
+}
+
+/// Stage of a trade deal that releases a share of the escrowed amount
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub event_type: u32, // Oracle event type that confirms the milestone
+    pub share_bps: u32, // Share of the escrowed amount in basis points
+}
+
+/// Asset the seller is paid in when it differs from the escrowed asset
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutTerms {
+    pub payout_asset: Address, // Seller's settlement asset, e.g. a local stablecoin
+    pub min_receive: i128, // Minimum payout for the full escrowed amount
 }
 
 /// Escrow status enum
 #[contracttype]
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EscrowStatus {
     Pending = 0,
     Active = 1,
     Released = 2,
     Cancelled = 3,
     Disputed = 4, // New: Dispute state
+    Resolved = 5, // Dispute settled by an arbiter ruling
+}
+
+/// M-of-N approval policy that releases an escrow without the oracle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalPolicy {
+    pub signers: Vec<Address>, // Parties and named third parties, e.g. an inspection agency
+    pub threshold: u32, // Approvals required to release
+}
+
+/// Evidence submitted by a party to a disputed escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Evidence {
+    pub submitter: Address,
+    pub evidence_hash: BytesN<32>, // Hash of the off-chain evidence bundle
+    pub submitted_at: u64,
 }
 
 /// Governance action types
@@ -105,9 +246,17 @@ pub enum EscrowStatus {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum GovernanceAction {
     UpdateMaxLTV(u32), // LTV in basis points (e.g., 8000 = 80%)
-    UpdateCollateralWhitelist(Symbol, bool), // Asset symbol, is_allowed
+    UpdateCollateralWhitelist(Symbol, bool), // Registry collateral type, is_allowed
     UpdateOracleWhitelist(Address, bool), // Oracle address, is_allowed
     UpgradeContract(BytesN<32>), // New Wasm Hash
+    UpdateRiskParameters(RiskParameters), // Global parameters for the configured risk engine
+    UpdateTypeRiskParameters(Symbol, RiskParameters), // Collateral type, parameters
+    SetArbiter(Address), // Address allowed to rule on disputes
+    UpdateEvidenceWindow(u64), // Evidence window in seconds
+    ResolveDispute(u64, u32), // Escrow ID, seller share in basis points
+    UpdateFeeConfig(FeeConfig), // Fees charged by the vault
+    UpdateLoanFeeConfig(FeeConfig), // Fees charged by the configured loan management contract
+    UpdateRiskFeeConfig(FeeConfig), // Fees charged by the configured risk engine
 }
 
 /// Proposal data structure
@@ -133,6 +282,15 @@ pub struct VoteRecord {
     pub weight: u128,
 }
 
+/// Maximum number of signers in an approval policy
+const MAX_SIGNERS: u32 = 10;
+
+/// Maximum number of milestones per escrow
+const MAX_MILESTONES: u32 = 10;
+
+/// Default time parties have to submit evidence after a dispute is raised (3 days)
+const DEFAULT_EVIDENCE_WINDOW: u64 = 259200;
+
 /// Main contract for StelloVault trade finance operations
 #[contract]
 pub struct StelloVaultContract;
@@ -155,6 +313,7 @@ impl StelloVaultContract {
         // Default protocol parameters
         env.storage().instance().set(&symbol_short!("max_ltv"), &7000u32); // 70% LTV default
         env.storage().instance().set(&symbol_short!("quorum"), &100u128); // Default quorum
+        env.storage().instance().set(&symbol_short!("evd_win"), &DEFAULT_EVIDENCE_WINDOW);
 
         env.events().publish(
             (symbol_short!("init"),),
@@ -171,64 +330,52 @@ impl StelloVaultContract {
             .unwrap()
     }
 
-    /// Tokenize collateral (create a new collateral token)
-    pub fn tokenize_collateral(
+    /// Create a trade escrow
+    ///
+    /// The escrow starts `Pending` and becomes `Active` once the buyer's
+    /// funds are held by the contract, either via `fund_escrow` or, with
+    /// `fund_now`, atomically as part of this call.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_escrow(
         env: Env,
-        owner: Address,
-        asset_type: Symbol,
-        asset_value: i128,
-        metadata: Symbol,
-        fractional_shares: u32,
+        buyer: Address,
+        seller: Address,
+        lender: Address,
+        collateral_token_id: u64,
+        amount: i128,
+        asset: Address,
+        oracle_address: Address,
+        release_conditions: Symbol,
+        expiry_ts: u64,
+        fund_now: bool,
     ) -> Result<u64, ContractError> {
-        owner.require_auth();
-
-        if asset_value <= 0 {
-            return Err(ContractError::InvalidAmount);
-        }
-
-        // Check Collateral Whitelist
-        if !env.storage().persistent().get::<_, bool>(&(symbol_short!("w_col"), asset_type.clone())).unwrap_or(false) {
-            return Err(ContractError::AssetNotWhitelisted);
-        }
-
-        let token_id: u64 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("tok_next"))
-            .unwrap_or(1);
+        buyer.require_auth();
 
-        let collateral = CollateralToken {
-            owner: owner.clone(),
-            asset_type,
-            asset_value,
-            metadata,
-            fractional_shares,
+        let escrow = TradeEscrow {
+            buyer,
+            seller,
+            lender,
+            collateral_token_id,
+            amount,
+            asset,
+            status: EscrowStatus::Pending, // Active once the buyer's funds are held
+            oracle_address,
+            release_conditions,
+            expiry_ts,
             created_at: env.ledger().timestamp(),
+            released_amount: 0,
         };
 
-        env.storage()
-            .persistent()
-            .set(&token_id, &collateral);
-
-        env.storage()
-            .instance()
-            .set(&symbol_short!("tok_next"), &(token_id + 1));
-
-        env.events().publish(
-            (symbol_short!("tokenize"),),
-            (token_id, owner, asset_value),
-        );
-
-        Ok(token_id)
-    }
-
-    /// Get collateral token details
-    pub fn get_collateral(env: Env, token_id: u64) -> Option<CollateralToken> {
-        env.storage().persistent().get(&token_id)
+        Self::open_escrow(&env, escrow, Vec::new(&env), fund_now)
     }
 
-    /// Create a trade escrow
-    pub fn create_escrow(
+    /// Create a trade escrow that pays out in milestone tranches
+    ///
+    /// Works like `create_escrow`, but the payout is split into ordered
+    /// milestones whose shares must add up to 100%. Each tranche is released
+    /// by the oracle via `release_milestone` once its event is confirmed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_milestone_escrow(
         env: Env,
         buyer: Address,
         seller: Address,
@@ -237,120 +384,367 @@ impl StelloVaultContract {
         amount: i128,
         asset: Address,
         oracle_address: Address,
-        release_conditions: Symbol,
         expiry_ts: u64,
+        milestones: Vec<Milestone>,
+        fund_now: bool,
     ) -> Result<u64, ContractError> {
         buyer.require_auth();
 
-        if amount <= 0 {
-            return Err(ContractError::InvalidAmount);
-        }
-
-        // Check Oracle Whitelist
-        if !env.storage().persistent().get::<_, bool>(&(symbol_short!("w_orc"), oracle_address.clone())).unwrap_or(false) {
-            return Err(ContractError::OracleNotWhitelisted);
-        }
-
-        // Verify collateral token exists and Check LTV
-        let collateral: CollateralToken = env.storage().persistent().get(&collateral_token_id).ok_or(ContractError::CollateralNotFound)?;
-
-        let max_ltv: u32 = env.storage().instance().get(&symbol_short!("max_ltv")).unwrap_or(0);
-
-        // Check for math overflow during LTV calculation
-        let adjusted_value = (collateral.asset_value as i128)
-            .checked_mul(max_ltv as i128)
-            .ok_or(ContractError::MathOverflow)?;
-
-        let max_loan_amount = adjusted_value / 10000;
-
-        if amount > max_loan_amount {
-            return Err(ContractError::LtvExceeded);
+        if milestones.is_empty() {
+            return Err(ContractError::InvalidMilestones);
         }
 
-        let escrow_id: u64 = env
-            .storage()
-            .instance()
-            .get(&symbol_short!("esc_next"))
-            .unwrap_or(1);
-
         let escrow = TradeEscrow {
-            buyer: buyer.clone(),
-            seller: seller.clone(),
+            buyer,
+            seller,
             lender,
             collateral_token_id,
             amount,
             asset,
-            status: EscrowStatus::Active, // Active immediately since funds are locked
+            status: EscrowStatus::Pending,
             oracle_address,
-            release_conditions,
+            release_conditions: symbol_short!("milestone"),
             expiry_ts,
             created_at: env.ledger().timestamp(),
+            released_amount: 0,
         };
 
-        env.storage().persistent().set(&escrow_id, &escrow);
+        Self::open_escrow(&env, escrow, milestones, fund_now)
+    }
 
-        env.storage()
-            .instance()
-            .set(&symbol_short!("esc_next"), &(escrow_id + 1));
+    /// Fund a pending escrow
+    ///
+    /// Pulls `amount` of `asset` from the buyer into the contract and marks
+    /// the escrow `Active`.
+    ///
+    /// # Events
+    /// Emits `esc_fund` with (escrow_id, buyer, amount)
+    pub fn fund_escrow(env: Env, escrow_id: u64) -> Result<(), ContractError> {
+        let mut escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
 
-        env.events().publish(
-            (symbol_short!("esc_crtd"),),
-            (escrow_id, buyer, seller, amount),
-        );
+        escrow.buyer.require_auth();
 
-        Ok(escrow_id)
+        if escrow.status != EscrowStatus::Pending {
+            return Err(ContractError::EscrowError);
+        }
+
+        if env.ledger().timestamp() > escrow.expiry_ts {
+            return Err(ContractError::EscrowExpired);
+        }
+
+        Self::pull_funds(&env, escrow_id, &mut escrow);
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+
+        Ok(())
+    }
+
+    /// Cancel an escrow that was never funded
+    ///
+    /// Either the buyer or the seller may walk away before funding. Nothing
+    /// is held by the contract, so there is nothing to refund.
+    ///
+    /// # Events
+    /// Emits `esc_cncl` with (escrow_id, caller)
+    pub fn cancel_escrow(env: Env, escrow_id: u64, caller: Address) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let mut escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        if caller != escrow.buyer && caller != escrow.seller {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if escrow.status != EscrowStatus::Pending {
+            return Err(ContractError::EscrowError);
+        }
+
+        escrow.status = EscrowStatus::Cancelled;
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+
+        Self::unlock_collateral(&env, escrow_id, escrow.collateral_token_id)?;
+
+        env.events()
+            .publish((symbol_short!("esc_cncl"),), (escrow_id, caller));
+        Ok(())
     }
 
     /// Release escrow funds (Oracle-triggered)
+    ///
+    /// On a milestone escrow this is the final sign-off and releases every
+    /// tranche not yet paid out.
     pub fn release_funds(env: Env, escrow_id: u64) -> Result<(), ContractError> {
         let mut escrow: TradeEscrow = env
             .storage()
             .persistent()
-            .get(&escrow_id)
+            .get(&(symbol_short!("escrow"), escrow_id))
             .ok_or(ContractError::EscrowNotFound)?;
 
         // Authorization: Oracle must sign off
-        // Real implementation: OracleAdapter checks condition
-        // Here we just require the stored oracle address to invoke this
+        // When the oracle is an OracleAdapter contract, it invokes this
+        // directly once the registered release condition is met
         escrow.oracle_address.require_auth();
 
         if escrow.status != EscrowStatus::Active {
             return Err(ContractError::EscrowError);
         }
 
-        // Transfer funds to Seller
-        let token_client = token::Client::new(&env, &escrow.asset);
-        token_client.transfer(&env.current_contract_address(), &escrow.seller, &escrow.amount);
+        Self::release_remaining(&env, escrow_id, &mut escrow)
+    }
 
-        escrow.status = EscrowStatus::Released;
-        env.storage().persistent().set(&escrow_id, &escrow);
+    /// Attach an M-of-N approval policy to an escrow
+    ///
+    /// Buyer, seller and lender must all agree to the policy, so neither
+    /// trading party can hand release to itself alone. It works alongside
+    /// the oracle: either the oracle's sign-off or `threshold` approvals from
+    /// `signers` releases the escrow. A policy cannot be changed once set.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - The escrow to govern
+    /// * `signers` - Addresses allowed to approve (at most 10, no duplicates)
+    /// * `threshold` - Approvals required, between 1 and the number of signers
+    ///
+    /// # Events
+    /// Emits `esc_pol` with (escrow_id, signers, threshold)
+    pub fn set_approval_policy(
+        env: Env,
+        escrow_id: u64,
+        signers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), ContractError> {
+        let escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
 
-        env.events().publish((symbol_short!("esc_rel"),), (escrow_id,));
+        escrow.buyer.require_auth();
+        escrow.seller.require_auth();
+        if escrow.lender != escrow.buyer && escrow.lender != escrow.seller {
+            escrow.lender.require_auth();
+        }
+
+        if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowError);
+        }
+
+        let policy_key = (symbol_short!("apr_pol"), escrow_id);
+        if env.storage().persistent().has(&policy_key) {
+            return Err(ContractError::InvalidApprovalPolicy);
+        }
+
+        if signers.is_empty() || signers.len() > MAX_SIGNERS || threshold == 0 || threshold > signers.len() {
+            return Err(ContractError::InvalidApprovalPolicy);
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers.iter().skip(i + 1).any(|other| other == signer) {
+                return Err(ContractError::InvalidApprovalPolicy);
+            }
+        }
+
+        let policy = ApprovalPolicy { signers: signers.clone(), threshold };
+        env.storage().persistent().set(&policy_key, &policy);
+
+        env.events().publish(
+            (symbol_short!("esc_pol"),),
+            (escrow_id, signers, threshold),
+        );
         Ok(())
     }
 
+    /// Record one signer's approval to release an escrow
+    ///
+    /// Once the policy threshold is reached the escrow is released to the
+    /// seller in the same call.
+    ///
+    /// # Returns
+    /// true if this approval released the escrow
+    ///
+    /// # Events
+    /// Emits `esc_appr` with (escrow_id, signer, approvals, threshold)
+    pub fn approve_release(env: Env, escrow_id: u64, signer: Address) -> Result<bool, ContractError> {
+        signer.require_auth();
+
+        let mut escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        let policy = Self::get_approval_policy(env.clone(), escrow_id)
+            .ok_or(ContractError::ApprovalPolicyNotFound)?;
+
+        if !policy.signers.contains(&signer) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowError);
+        }
+
+        let approval_key = (symbol_short!("approval"), escrow_id, signer.clone());
+        if env.storage().persistent().has(&approval_key) {
+            return Err(ContractError::AlreadyApproved);
+        }
+        env.storage().persistent().set(&approval_key, &env.ledger().timestamp());
+
+        let approvals = Self::get_approval_count(env.clone(), escrow_id) + 1;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("apr_cnt"), escrow_id), &approvals);
+
+        env.events().publish(
+            (symbol_short!("esc_appr"),),
+            (escrow_id, signer, approvals, policy.threshold),
+        );
+
+        if approvals >= policy.threshold {
+            Self::release_remaining(&env, escrow_id, &mut escrow)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Get the approval policy of an escrow, if any
+    pub fn get_approval_policy(env: Env, escrow_id: u64) -> Option<ApprovalPolicy> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("apr_pol"), escrow_id))
+    }
+
+    /// Get the number of approvals recorded for an escrow
+    pub fn get_approval_count(env: Env, escrow_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("apr_cnt"), escrow_id))
+            .unwrap_or(0)
+    }
+
+    /// Check whether a signer has approved an escrow's release
+    pub fn has_approved(env: Env, escrow_id: u64, signer: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&(symbol_short!("approval"), escrow_id, signer))
+    }
+
+    /// Release the next milestone tranche of an escrow (Oracle-triggered)
+    ///
+    /// Milestones pay out strictly in order: `event_type` must match the
+    /// next unreleased milestone. The final milestone releases whatever
+    /// remains, so rounding never strands funds in the contract.
+    ///
+    /// # Events
+    /// Emits `ms_rel` with (escrow_id, milestone_index, tranche, released_amount)
+    pub fn release_milestone(env: Env, escrow_id: u64, event_type: u32) -> Result<i128, ContractError> {
+        let mut escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        escrow.oracle_address.require_auth();
+
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowError);
+        }
+
+        let milestones = Self::get_milestones(env.clone(), escrow_id);
+        let next_key = (symbol_short!("ms_next"), escrow_id);
+        let index: u32 = env.storage().persistent().get(&next_key).unwrap_or(0);
+        let milestone = milestones.get(index).ok_or(ContractError::MilestoneOutOfOrder)?;
+
+        if milestone.event_type != event_type {
+            return Err(ContractError::MilestoneOutOfOrder);
+        }
+
+        let is_last = index + 1 == milestones.len();
+        let tranche = if is_last {
+            escrow.amount - escrow.released_amount
+        } else {
+            escrow.amount
+                .checked_mul(milestone.share_bps as i128)
+                .ok_or(ContractError::MathOverflow)?
+                / 10000
+        };
+
+        Self::pay_seller(&env, escrow_id, &escrow, tranche)?;
+
+        escrow.released_amount += tranche;
+        if is_last {
+            escrow.status = EscrowStatus::Released;
+            Self::unlock_collateral(&env, escrow_id, escrow.collateral_token_id)?;
+        }
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+        env.storage().persistent().set(&next_key, &(index + 1));
+
+        env.events().publish(
+            (symbol_short!("ms_rel"),),
+            (escrow_id, index, tranche, escrow.released_amount),
+        );
+
+        if is_last {
+            env.events().publish((symbol_short!("esc_rel"),), (escrow_id,));
+        }
+
+        Ok(tranche)
+    }
+
+    /// Get the milestones of an escrow (empty for single-release escrows)
+    pub fn get_milestones(env: Env, escrow_id: u64) -> Vec<Milestone> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("mstones"), escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the index of the next milestone awaiting release
+    pub fn get_next_milestone(env: Env, escrow_id: u64) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ms_next"), escrow_id))
+            .unwrap_or(0)
+    }
+
     /// Expire escrow (Refund Buyer if time passed)
+    ///
+    /// An escrow that was never funded holds nothing to refund; expiring it
+    /// only unlocks the collateral.
     pub fn expire_escrow(env: Env, escrow_id: u64) -> Result<(), ContractError> {
         let mut escrow: TradeEscrow = env
             .storage()
             .persistent()
-            .get(&escrow_id)
+            .get(&(symbol_short!("escrow"), escrow_id))
             .ok_or(ContractError::EscrowNotFound)?;
 
         if env.ledger().timestamp() <= escrow.expiry_ts {
             return Err(ContractError::EscrowNotExpired);
         }
 
-        if escrow.status != EscrowStatus::Active {
-            return Err(ContractError::EscrowError);
+        match escrow.status {
+            EscrowStatus::Active => {
+                // Refund Buyer whatever has not been released
+                let token_client = token::Client::new(&env, &escrow.asset);
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &escrow.buyer,
+                    &(escrow.amount - escrow.released_amount),
+                );
+            }
+            EscrowStatus::Pending => {}
+            _ => return Err(ContractError::EscrowError),
         }
 
-        // Refund Buyer
-        let token_client = token::Client::new(&env, &escrow.asset);
-        token_client.transfer(&env.current_contract_address(), &escrow.buyer, &escrow.amount);
-
         escrow.status = EscrowStatus::Cancelled;
-        env.storage().persistent().set(&escrow_id, &escrow);
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+
+        Self::unlock_collateral(&env, escrow_id, escrow.collateral_token_id)?;
 
         env.events()
             .publish((symbol_short!("esc_exp"),), (escrow_id,));
@@ -364,7 +758,7 @@ impl StelloVaultContract {
         let mut escrow: TradeEscrow = env
             .storage()
             .persistent()
-            .get(&escrow_id)
+            .get(&(symbol_short!("escrow"), escrow_id))
             .ok_or(ContractError::EscrowNotFound)?;
 
         // Only Buyer, Seller, or Lender can raise dispute
@@ -377,21 +771,323 @@ impl StelloVaultContract {
         }
 
         escrow.status = EscrowStatus::Disputed;
-        env.storage().persistent().set(&escrow_id, &escrow);
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("dsp_at"), escrow_id), &env.ledger().timestamp());
 
         env.events()
             .publish((symbol_short!("esc_dsp"),), (escrow_id,));
         Ok(())
     }
 
-    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<TradeEscrow> {
-        env.storage().persistent().get(&escrow_id)
-    }
+    /// Submit evidence for a disputed escrow
+    ///
+    /// Buyer, seller and lender may each submit one evidence hash while the
+    /// evidence window is open; a later submission replaces the earlier one.
+    ///
+    /// # Events
+    /// Emits `evidence` with (escrow_id, caller, evidence_hash)
+    pub fn submit_evidence(
+        env: Env,
+        escrow_id: u64,
+        caller: Address,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
 
-    // --- Governance Functions ---
+        let escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
 
-    /// Create a new proposal
-    pub fn propose(
+        if caller != escrow.buyer && caller != escrow.seller && caller != escrow.lender {
+            return Err(ContractError::Unauthorized);
+        }
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(ContractError::EscrowError);
+        }
+
+        if env.ledger().timestamp() > Self::evidence_deadline(&env, escrow_id) {
+            return Err(ContractError::EvidenceWindowClosed);
+        }
+
+        let evidence = Evidence {
+            submitter: caller.clone(),
+            evidence_hash: evidence_hash.clone(),
+            submitted_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("evidence"), escrow_id, caller.clone()), &evidence);
+
+        env.events().publish(
+            (symbol_short!("evidence"),),
+            (escrow_id, caller, evidence_hash),
+        );
+        Ok(())
+    }
+
+    /// Get the evidence submitted for a disputed escrow (buyer, seller, lender order)
+    pub fn get_evidence(env: Env, escrow_id: u64) -> Vec<Evidence> {
+        let mut submissions = Vec::new(&env);
+
+        if let Some(escrow) = Self::get_escrow(env.clone(), escrow_id) {
+            for party in [escrow.buyer, escrow.seller, escrow.lender] {
+                if let Some(evidence) = env
+                    .storage()
+                    .persistent()
+                    .get::<_, Evidence>(&(symbol_short!("evidence"), escrow_id, party))
+                {
+                    submissions.push_back(evidence);
+                }
+            }
+        }
+
+        submissions
+    }
+
+    /// Rule on a disputed escrow (arbiter only)
+    ///
+    /// Callable once the evidence window has closed. The escrowed funds are
+    /// split between seller and buyer and the collateral is unlocked. The
+    /// ruling never moves the collateral itself, which may belong to a third
+    /// party or back the lender's loan.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - The disputed escrow
+    /// * `seller_bps` - Seller's share of the escrowed funds in basis points
+    ///
+    /// # Events
+    /// Emits `dsp_res` with (escrow_id, seller_amount, buyer_amount)
+    pub fn resolve_dispute(env: Env, escrow_id: u64, seller_bps: u32) -> Result<(), ContractError> {
+        let arbiter: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("arbiter"))
+            .ok_or(ContractError::Unauthorized)?;
+        arbiter.require_auth();
+
+        Self::settle_dispute(&env, escrow_id, seller_bps)
+    }
+
+    /// Set the CollateralRegistry escrows lock collateral in (admin only)
+    ///
+    /// The vault must also be configured as the registry's escrow manager.
+    pub fn set_collateral_registry(env: Env, registry: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone());
+        admin.require_auth();
+
+        env.storage().instance().set(&symbol_short!("coll_reg"), &registry);
+
+        env.events().publish((symbol_short!("reg_set"),), (registry,));
+        Ok(())
+    }
+
+    /// Set the RiskAssessment contract governed through proposals (admin only)
+    ///
+    /// The vault must also be configured as the risk engine's governance address.
+    pub fn set_risk_engine(env: Env, risk_engine: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone());
+        admin.require_auth();
+
+        env.storage().instance().set(&symbol_short!("risk_eng"), &risk_engine);
+
+        env.events().publish((symbol_short!("risk_set"),), (risk_engine,));
+        Ok(())
+    }
+
+    /// Set the LoanManagement contract governed through proposals (admin only)
+    ///
+    /// The vault must also be configured as the loan contract's governance address.
+    pub fn set_loan_management(env: Env, loan_management: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone());
+        admin.require_auth();
+
+        env.storage().instance().set(&symbol_short!("loan_mgr"), &loan_management);
+
+        env.events().publish((symbol_short!("loan_set"),), (loan_management,));
+        Ok(())
+    }
+
+    /// Set the swap adapter used for cross-asset payouts (admin only)
+    pub fn set_swap_adapter(env: Env, adapter: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone());
+        admin.require_auth();
+
+        env.storage().instance().set(&symbol_short!("swap_adp"), &adapter);
+
+        env.events().publish((symbol_short!("swap_set"),), (adapter,));
+        Ok(())
+    }
+
+    /// Get the configured swap adapter address
+    pub fn get_swap_adapter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("swap_adp"))
+    }
+
+    /// Have the seller paid in a different asset (seller only)
+    ///
+    /// Each release swaps the escrowed asset into `payout_asset` through the
+    /// swap adapter. Each swap must return at least its pro-rata share of
+    /// `min_receive`, measured on the amount left after protocol fees.
+    /// Disputed escrows are still settled in the escrowed asset.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - Pending or active escrow
+    /// * `payout_asset` - Asset the seller wants to receive
+    /// * `min_receive` - Minimum payout for the full escrowed amount
+    ///
+    /// # Events
+    /// Emits `esc_pout` with (escrow_id, payout_asset, min_receive)
+    pub fn set_payout_terms(
+        env: Env,
+        escrow_id: u64,
+        payout_asset: Address,
+        min_receive: i128,
+    ) -> Result<(), ContractError> {
+        let escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        escrow.seller.require_auth();
+
+        if escrow.status != EscrowStatus::Pending && escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowError);
+        }
+
+        if min_receive <= 0 || payout_asset == escrow.asset {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        if Self::get_swap_adapter(env.clone()).is_none() {
+            return Err(ContractError::SwapAdapterNotConfigured);
+        }
+
+        let terms = PayoutTerms { payout_asset: payout_asset.clone(), min_receive };
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("payout"), escrow_id), &terms);
+
+        env.events().publish(
+            (symbol_short!("esc_pout"),),
+            (escrow_id, payout_asset, min_receive),
+        );
+        Ok(())
+    }
+
+    /// Get the payout terms of an escrow, if the seller is paid in another asset
+    pub fn get_payout_terms(env: Env, escrow_id: u64) -> Option<PayoutTerms> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("payout"), escrow_id))
+    }
+
+    /// Get the protocol fee configuration, if fees are enabled
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&symbol_short!("fee_cfg"))
+    }
+
+    /// Get cumulative release fees paid to the treasury in an asset
+    pub fn get_accrued_fees(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("fees"), asset))
+            .unwrap_or(0)
+    }
+
+    /// Get the configured CollateralRegistry address
+    pub fn get_collateral_registry(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("coll_reg"))
+    }
+
+    /// Get the configured RiskAssessment address
+    pub fn get_risk_engine(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("risk_eng"))
+    }
+
+    /// Get the configured LoanManagement address
+    pub fn get_loan_management(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("loan_mgr"))
+    }
+
+    /// Get the current dispute arbiter, if one has been appointed
+    pub fn get_arbiter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("arbiter"))
+    }
+
+    /// Register an oracle release condition for an escrow
+    ///
+    /// The escrow's oracle address must be an OracleAdapter contract. Once each
+    /// event type has `quorum` confirmations, the adapter calls `release_funds`.
+    /// For a milestone escrow `event_types` must list the milestones' event
+    /// types in order, and the adapter calls `release_milestone` as each one
+    /// is confirmed.
+    pub fn set_release_condition(
+        env: Env,
+        escrow_id: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+    ) -> Result<(), ContractError> {
+        let escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        escrow.buyer.require_auth();
+
+        if escrow.status != EscrowStatus::Active {
+            return Err(ContractError::EscrowError);
+        }
+
+        let adapter = OracleAdapterClient::new(&env, &escrow.oracle_address);
+        let milestones = Self::get_milestones(env.clone(), escrow_id);
+        if milestones.is_empty() {
+            adapter.register_release_condition(
+                &env.current_contract_address(),
+                &Self::oracle_escrow_id(&env, escrow_id),
+                &escrow_id,
+                &event_types,
+                &quorum,
+            );
+        } else {
+            let mut milestone_events = Vec::new(&env);
+            for milestone in milestones.iter() {
+                milestone_events.push_back(milestone.event_type);
+            }
+            if event_types != milestone_events {
+                return Err(ContractError::InvalidMilestones);
+            }
+
+            adapter.register_milestone_condition(
+                &env.current_contract_address(),
+                &Self::oracle_escrow_id(&env, escrow_id),
+                &escrow_id,
+                &event_types,
+                &quorum,
+            );
+        }
+
+        env.events().publish(
+            (symbol_short!("esc_cond"),),
+            (escrow_id, event_types, quorum),
+        );
+        Ok(())
+    }
+
+    pub fn get_escrow(env: Env, escrow_id: u64) -> Option<TradeEscrow> {
+        env.storage().persistent().get(&(symbol_short!("escrow"), escrow_id))
+    }
+
+    // --- Governance Functions ---
+
+    /// Create a new proposal
+    pub fn propose(
         env: Env,
         proposer: Address,
         title: Symbol,
@@ -524,6 +1220,43 @@ impl StelloVaultContract {
             GovernanceAction::UpgradeContract(wasm_hash) => {
                 env.deployer().update_current_contract_wasm(wasm_hash);
             },
+            GovernanceAction::UpdateRiskParameters(params) => {
+                Self::risk_engine_client(&env)?.update_risk_parameters(&params);
+            },
+            GovernanceAction::UpdateTypeRiskParameters(collateral_type, params) => {
+                Self::risk_engine_client(&env)?.update_type_risk_parameters(&collateral_type, &params);
+            },
+            GovernanceAction::SetArbiter(arbiter) => {
+                env.storage().instance().set(&symbol_short!("arbiter"), &arbiter);
+            },
+            GovernanceAction::UpdateEvidenceWindow(window) => {
+                env.storage().instance().set(&symbol_short!("evd_win"), &window);
+            },
+            GovernanceAction::ResolveDispute(escrow_id, seller_bps) => {
+                Self::settle_dispute(&env, escrow_id, seller_bps)?;
+            },
+            GovernanceAction::UpdateFeeConfig(config) => {
+                // The vault only charges the release fee
+                if config.escrow_release_bps > 10000 {
+                    return Err(ContractError::InvalidFeeConfig);
+                }
+                env.storage().instance().set(&symbol_short!("fee_cfg"), &config);
+                env.events().publish(
+                    (symbol_short!("fee_upd"),),
+                    (config.escrow_release_bps, config.treasury),
+                );
+            },
+            GovernanceAction::UpdateLoanFeeConfig(config) => {
+                let loan_management: Address = env
+                    .storage()
+                    .instance()
+                    .get(&symbol_short!("loan_mgr"))
+                    .ok_or(ContractError::LoanManagementNotConfigured)?;
+                LoanManagementClient::new(&env, &loan_management).update_fee_config(&config);
+            },
+            GovernanceAction::UpdateRiskFeeConfig(config) => {
+                Self::risk_engine_client(&env)?.update_fee_config(&config);
+            },
         }
 
         proposal.executed = true;
@@ -537,195 +1270,1165 @@ impl StelloVaultContract {
         Ok(())
     }
 
-    // Internal helper for sqrt
-    fn sqrt(n: u128) -> u128 {
-        if n < 2 {
-            return n;
-        }
-        let mut x = n / 2;
-        let mut y = (x + n / x) / 2;
-        while y < x {
-            x = y;
-            y = (x + n / x) / 2;
-        }
-        x
+    fn risk_engine_client(env: &Env) -> Result<RiskAssessmentClient<'_>, ContractError> {
+        let risk_engine: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("risk_eng"))
+            .ok_or(ContractError::RiskEngineNotConfigured)?;
+
+        Ok(RiskAssessmentClient::new(env, &risk_engine))
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{testutils::{Address as _, Ledger}, Env};
+    fn registry_client(env: &Env) -> Result<CollateralRegistryClient<'_>, ContractError> {
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::RegistryNotConfigured)?;
+        Ok(CollateralRegistryClient::new(env, &registry))
+    }
 
-    #[test]
-    fn test_initialize() {
-        let env = Env::default();
-        env.mock_all_auths();
+    /// Unlock an escrow's collateral in the registry once the deal has ended
+    ///
+    /// Collateral the risk engine already released after a liquidation, and
+    /// possibly pledged to another deal since, is left alone.
+    fn unlock_collateral(env: &Env, escrow_id: u64, collateral_id: u64) -> Result<(), ContractError> {
+        let registry = Self::registry_client(env)?;
+        let collateral = registry
+            .get_collateral(&collateral_id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        let held = collateral.locked
+            && collateral.lock_holder == Some(env.current_contract_address())
+            && collateral.lock_ref == escrow_id;
+        if held {
+            registry.unlock_collateral(&collateral_id, &escrow_id);
+        }
+        Ok(())
+    }
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register(StelloVaultContract, ());
-        let client = StelloVaultContractClient::new(&env, &contract_id);
+    /// Pay every tranche not yet released to the seller and close the escrow
+    fn release_remaining(env: &Env, escrow_id: u64, escrow: &mut TradeEscrow) -> Result<(), ContractError> {
+        let remaining = escrow.amount - escrow.released_amount;
+        Self::pay_seller(env, escrow_id, escrow, remaining)?;
 
-        let gov_token = Address::generate(&env);
-        client.initialize(&admin, &gov_token);
+        escrow.released_amount = escrow.amount;
+        escrow.status = EscrowStatus::Released;
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), escrow);
 
-        let admin_result = client.admin();
-        assert_eq!(admin_result, admin);
+        Self::unlock_collateral(env, escrow_id, escrow.collateral_token_id)?;
 
-        // Check default LTV via storage inspection
-        env.as_contract(&contract_id, || {
-            let max_ltv: u32 = env.storage().instance().get(&symbol_short!("max_ltv")).unwrap();
-            assert_eq!(max_ltv, 7000);
-        });
+        env.events().publish((symbol_short!("esc_rel"),), (escrow_id,));
+        Ok(())
     }
 
-    #[test]
-    fn test_tokenize_collateral() {
-        let env = Env::default();
-        env.mock_all_auths();
-
-        let admin = Address::generate(&env);
-        let owner = Address::generate(&env);
-        let contract_id = env.register(StelloVaultContract, ());
-        let client = StelloVaultContractClient::new(&env, &contract_id);
-
-        let gov_token = Address::generate(&env);
-        client.initialize(&admin, &gov_token);
-
-        // Whitelist the asset manually
-        let asset = Symbol::new(&env, "INVOICE");
-        env.as_contract(&contract_id, || {
-            env.storage().persistent().set(&(symbol_short!("w_col"), asset.clone()), &true);
-        });
+    /// Pay `amount` of the escrowed asset to the seller, swapping it into
+    /// the seller's payout asset when payout terms are set
+    ///
+    /// The protocol's release fee is taken first. The minimum receive is
+    /// pro-rated for partial releases. A swap that delivers less fails the
+    /// whole release, leaving the funds in escrow.
+    fn pay_seller(env: &Env, escrow_id: u64, escrow: &TradeEscrow, amount: i128) -> Result<(), ContractError> {
+        let token_client = token::Client::new(env, &escrow.asset);
+
+        let fee = Self::collect_release_fee(env, escrow_id, &escrow.asset, amount)?;
+        let amount = amount - fee;
+
+        let terms = match Self::get_payout_terms(env.clone(), escrow_id) {
+            Some(terms) => terms,
+            None => {
+                token_client.transfer(&env.current_contract_address(), &escrow.seller, &amount);
+                return Ok(());
+            }
+        };
 
-        let token_id = client.tokenize_collateral(
-            &owner,
-            &asset,
-            &10000,
-            &Symbol::new(&env, "META"),
-            &100
+        let adapter: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("swap_adp"))
+            .ok_or(ContractError::SwapAdapterNotConfigured)?;
+
+        // The seller's minimum scales with what is actually swapped, net of fees
+        let min_out = terms.min_receive
+            .checked_mul(amount)
+            .ok_or(ContractError::MathOverflow)?
+            / escrow.amount;
+
+        // Measure what the seller actually received instead of trusting the adapter
+        let payout_client = token::Client::new(env, &terms.payout_asset);
+        let balance_before = payout_client.balance(&escrow.seller);
+
+        token_client.transfer(&env.current_contract_address(), &adapter, &amount);
+        SwapAdapterClient::new(env, &adapter).swap(
+            &escrow.asset,
+            &amount,
+            &terms.payout_asset,
+            &min_out,
+            &escrow.seller,
         );
 
-        assert_eq!(token_id, 1);
+        let received = payout_client.balance(&escrow.seller) - balance_before;
+        if received < min_out {
+            return Err(ContractError::SlippageExceeded);
+        }
 
-        let collateral = client.get_collateral(&token_id).unwrap();
-        assert_eq!(collateral.owner, owner);
-        assert_eq!(collateral.asset_value, 10000);
+        env.events().publish(
+            (symbol_short!("esc_swap"),),
+            (escrow_id, amount, terms.payout_asset, received),
+        );
+        Ok(())
     }
 
-    #[test]
-    fn test_create_and_release_escrow() {
-        let env = Env::default();
-        env.mock_all_auths();
+    /// Send the protocol's share of a release to the treasury
+    fn collect_release_fee(env: &Env, escrow_id: u64, asset: &Address, amount: i128) -> Result<i128, ContractError> {
+        let config = match Self::get_fee_config(env.clone()) {
+            Some(config) => config,
+            None => return Ok(0),
+        };
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register(StelloVaultContract, ());
-        let client = StelloVaultContractClient::new(&env, &contract_id);
+        let fee = amount
+            .checked_mul(config.escrow_release_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
 
-        let gov_token = Address::generate(&env);
-        client.initialize(&admin, &gov_token);
+        if fee > 0 {
+            token::Client::new(env, asset).transfer(&env.current_contract_address(), &config.treasury, &fee);
 
-        let buyer = Address::generate(&env);
-        let seller = Address::generate(&env);
-        let lender = Address::generate(&env);
-        let oracle = Address::generate(&env);
+            let fees_key = (symbol_short!("fees"), asset.clone());
+            let accrued: i128 = env.storage().persistent().get(&fees_key).unwrap_or(0);
+            env.storage().persistent().set(&fees_key, &(accrued + fee));
 
-        // Whitelist oracle and collateral
-        let asset_type = Symbol::new(&env, "INVOICE");
-        env.as_contract(&contract_id, || {
-            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
-            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
-        });
+            env.events().publish(
+                (symbol_short!("fee_paid"),),
+                (escrow_id, asset.clone(), fee),
+            );
+        }
 
-        // Create collateral
-        let token_id = client.tokenize_collateral(
-            &buyer,
-            &asset_type,
-            &10000,
-            &Symbol::new(&env, "META"),
-            &100
-        );
+        Ok(fee)
+    }
 
-        // Create token for payment
-        let token_admin = Address::generate(&env);
-        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
-        let token_address = token_contract.address();
-        let token = token::Client::new(&env, &token_address);
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
+    /// Move the escrowed amount from the buyer into the contract and
+    /// activate the escrow
+    fn pull_funds(env: &Env, escrow_id: u64, escrow: &mut TradeEscrow) {
+        let token_client = token::Client::new(env, &escrow.asset);
+        token_client.transfer(&escrow.buyer, &env.current_contract_address(), &escrow.amount);
 
-        token_admin_client.mint(&buyer, &10000);
+        escrow.status = EscrowStatus::Active;
 
-        let expiry = env.ledger().timestamp() + 1000;
-        let escrow_id = client.create_escrow(
-            &buyer,
-            &seller,
-            &lender,
-            &token_id,
-            &7000, // 70% LTV of 10000 = 7000 max
-            &token_address,
-            &oracle,
-            &symbol_short!("ship_del"),
-            &expiry
+        env.events().publish(
+            (symbol_short!("esc_fund"),),
+            (escrow_id, escrow.buyer.clone(), escrow.amount),
         );
+    }
 
-        client.release_funds(&escrow_id);
+    /// Validate a new escrow against the whitelists and LTV limit, store it
+    /// and optionally fund it from the buyer
+    fn open_escrow(
+        env: &Env,
+        mut escrow: TradeEscrow,
+        milestones: Vec<Milestone>,
+        fund_now: bool,
+    ) -> Result<u64, ContractError> {
+        if escrow.amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
 
-        let escrow = client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Released);
-    }
+        Self::validate_milestones(&milestones)?;
 
-    #[test]
-    fn test_escrow_expiry() {
-        let env = Env::default();
-        env.mock_all_auths();
+        // Check Oracle Whitelist
+        if !env.storage().persistent().get::<_, bool>(&(symbol_short!("w_orc"), escrow.oracle_address.clone())).unwrap_or(false) {
+            return Err(ContractError::OracleNotWhitelisted);
+        }
 
-        let admin = Address::generate(&env);
-        let contract_id = env.register(StelloVaultContract, ());
-        let client = StelloVaultContractClient::new(&env, &contract_id);
+        // Verify collateral exists in the registry and Check LTV
+        let registry = Self::registry_client(env)?;
+        let collateral = registry
+            .get_collateral(&escrow.collateral_token_id)
+            .ok_or(ContractError::CollateralNotFound)?;
 
-        let gov_token = Address::generate(&env);
-        client.initialize(&admin, &gov_token);
+        if collateral.expiry_ts <= env.ledger().timestamp() {
+            return Err(ContractError::CollateralExpired);
+        }
 
-        let buyer = Address::generate(&env);
-        let seller = Address::generate(&env);
-        let lender = Address::generate(&env);
-        let oracle = Address::generate(&env);
+        // Check Collateral Whitelist against the registry's collateral type
+        let collateral_type = registry
+            .get_collateral_type(&escrow.collateral_token_id)
+            .ok_or(ContractError::AssetNotWhitelisted)?;
+        if !env.storage().persistent().get::<_, bool>(&(symbol_short!("w_col"), collateral_type)).unwrap_or(false) {
+            return Err(ContractError::AssetNotWhitelisted);
+        }
 
-        // Whitelist oracle and collateral
-        let asset_type = Symbol::new(&env, "INVOICE");
+        // Pledging someone else's collateral needs its owner's consent
+        if collateral.owner != escrow.buyer {
+            collateral.owner.require_auth();
+        }
+
+        let max_ltv: u32 = env.storage().instance().get(&symbol_short!("max_ltv")).unwrap_or(0);
+
+        // Shares seized in earlier liquidations no longer back the borrower
+        let pledgeable_value = registry.get_pledgeable_value(&escrow.collateral_token_id);
+
+        // Check for math overflow during LTV calculation
+        let adjusted_value = pledgeable_value
+            .checked_mul(max_ltv as i128)
+            .ok_or(ContractError::MathOverflow)?;
+
+        let max_loan_amount = adjusted_value / 10000;
+
+        if escrow.amount > max_loan_amount {
+            return Err(ContractError::LtvExceeded);
+        }
+
+        let escrow_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("esc_next"))
+            .unwrap_or(1);
+
+        // Locking fails if the collateral already backs another deal, which
+        // enforces the double-financing check on-chain, or if the lender
+        // does not hold the eBL behind it
+        registry.lock_collateral(&escrow.collateral_token_id, &escrow_id, &escrow.lender);
+
+        if !milestones.is_empty() {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("mstones"), escrow_id), &milestones);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("esc_next"), &(escrow_id + 1));
+
+        env.events().publish(
+            (symbol_short!("esc_crtd"),),
+            (escrow_id, escrow.buyer.clone(), escrow.seller.clone(), escrow.amount),
+        );
+
+        if fund_now {
+            Self::pull_funds(env, escrow_id, &mut escrow);
+        }
+
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+
+        Ok(escrow_id)
+    }
+
+    /// Check that milestone shares are positive and add up to 100%
+    fn validate_milestones(milestones: &Vec<Milestone>) -> Result<(), ContractError> {
+        if milestones.is_empty() {
+            return Ok(());
+        }
+
+        if milestones.len() > MAX_MILESTONES {
+            return Err(ContractError::InvalidMilestones);
+        }
+
+        let mut total_bps = 0u32;
+        for milestone in milestones.iter() {
+            if milestone.share_bps == 0 {
+                return Err(ContractError::InvalidMilestones);
+            }
+            total_bps = total_bps.saturating_add(milestone.share_bps);
+        }
+
+        if total_bps != 10000 {
+            return Err(ContractError::InvalidMilestones);
+        }
+        Ok(())
+    }
+
+    /// Last moment evidence may be submitted for a disputed escrow
+    fn evidence_deadline(env: &Env, escrow_id: u64) -> u64 {
+        let disputed_at: u64 = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("dsp_at"), escrow_id))
+            .unwrap_or(0);
+        let window: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("evd_win"))
+            .unwrap_or(DEFAULT_EVIDENCE_WINDOW);
+
+        disputed_at.saturating_add(window)
+    }
+
+    /// Apply a dispute ruling: split the funds and unlock the collateral
+    fn settle_dispute(env: &Env, escrow_id: u64, seller_bps: u32) -> Result<(), ContractError> {
+        let mut escrow: TradeEscrow = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("escrow"), escrow_id))
+            .ok_or(ContractError::EscrowNotFound)?;
+
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(ContractError::EscrowError);
+        }
+
+        if seller_bps > 10000 {
+            return Err(ContractError::InvalidRuling);
+        }
+
+        if env.ledger().timestamp() <= Self::evidence_deadline(env, escrow_id) {
+            return Err(ContractError::EvidenceWindowOpen);
+        }
+
+        // Milestones already paid out are not reopened by the ruling
+        let disputed_amount = escrow.amount - escrow.released_amount;
+        let seller_amount = disputed_amount
+            .checked_mul(seller_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+        let buyer_amount = disputed_amount - seller_amount;
+
+        // The seller's share is paid like any release, net of the protocol
+        // fee and in the seller's payout asset
+        if seller_amount > 0 {
+            Self::pay_seller(env, escrow_id, &escrow, seller_amount)?;
+        }
+        if buyer_amount > 0 {
+            token::Client::new(env, &escrow.asset)
+                .transfer(&env.current_contract_address(), &escrow.buyer, &buyer_amount);
+        }
+
+        Self::unlock_collateral(env, escrow_id, escrow.collateral_token_id)?;
+
+        escrow.released_amount += seller_amount;
+        escrow.status = EscrowStatus::Resolved;
+        env.storage().persistent().set(&(symbol_short!("escrow"), escrow_id), &escrow);
+
+        env.events().publish(
+            (symbol_short!("dsp_res"),),
+            (escrow_id, seller_amount, buyer_amount),
+        );
+        Ok(())
+    }
+
+    // Escrow ID as confirmed by oracles on the OracleAdapter
+    fn oracle_escrow_id(env: &Env, escrow_id: u64) -> Bytes {
+        Bytes::from_slice(env, &escrow_id.to_be_bytes())
+    }
+
+    // Internal helper for sqrt
+    fn sqrt(n: u128) -> u128 {
+        if n < 2 {
+            return n;
+        }
+        let mut x = n / 2;
+        let mut y = (x + n / x) / 2;
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::{Address as _, Ledger}, vec, Env};
+
+    /// Minimal oracle adapter that stores the registered condition and
+    /// calls back into the escrow contract on demand
+    #[contract]
+    pub struct MockOracleAdapter;
+
+    #[contractimpl]
+    impl MockOracleAdapter {
+        pub fn register_release_condition(
+            env: Env,
+            escrow_contract: Address,
+            escrow_id: Bytes,
+            escrow_ref: u64,
+            event_types: Vec<u32>,
+            quorum: u32,
+        ) {
+            escrow_contract.require_auth();
+            env.storage().instance().set(&symbol_short!("cond"), &(escrow_contract, escrow_id, escrow_ref, event_types, quorum));
+        }
+
+        pub fn register_milestone_condition(
+            env: Env,
+            escrow_contract: Address,
+            escrow_id: Bytes,
+            escrow_ref: u64,
+            event_types: Vec<u32>,
+            quorum: u32,
+        ) {
+            escrow_contract.require_auth();
+            env.storage().instance().set(&symbol_short!("ms_cond"), &(escrow_contract, escrow_id, escrow_ref, event_types, quorum));
+        }
+
+        pub fn fire(env: Env) {
+            let (escrow_contract, _, escrow_ref, _, _): (Address, Bytes, u64, Vec<u32>, u32) =
+                env.storage().instance().get(&symbol_short!("cond")).unwrap();
+            StelloVaultContractClient::new(&env, &escrow_contract).release_funds(&escrow_ref);
+        }
+
+        pub fn fire_milestone(env: Env, event_type: u32) -> i128 {
+            let (escrow_contract, _, escrow_ref, _, _): (Address, Bytes, u64, Vec<u32>, u32) =
+                env.storage().instance().get(&symbol_short!("ms_cond")).unwrap();
+            StelloVaultContractClient::new(&env, &escrow_contract).release_milestone(&escrow_ref, &event_type)
+        }
+    }
+
+    /// Minimal risk engine that only accepts updates from its governance address
+    #[contract]
+    pub struct MockRiskAssessment;
+
+    #[contractimpl]
+    impl MockRiskAssessment {
+        pub fn set_governance(env: Env, governance: Address) {
+            env.storage().instance().set(&symbol_short!("gov"), &governance);
+        }
+
+        pub fn update_risk_parameters(env: Env, new_params: RiskParameters) {
+            let governance: Address = env.storage().instance().get(&symbol_short!("gov")).unwrap();
+            governance.require_auth();
+            env.storage().instance().set(&symbol_short!("pending"), &new_params);
+        }
+
+        pub fn update_type_risk_parameters(env: Env, collateral_type: Symbol, new_params: RiskParameters) {
+            let governance: Address = env.storage().instance().get(&symbol_short!("gov")).unwrap();
+            governance.require_auth();
+            env.storage().persistent().set(&collateral_type, &new_params);
+        }
+
+        pub fn get_pending(env: Env) -> Option<RiskParameters> {
+            env.storage().instance().get(&symbol_short!("pending"))
+        }
+
+        pub fn update_fee_config(env: Env, config: FeeConfig) {
+            let governance: Address = env.storage().instance().get(&symbol_short!("gov")).unwrap();
+            governance.require_auth();
+            env.storage().instance().set(&symbol_short!("fee_cfg"), &config);
+        }
+
+        pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+            env.storage().instance().get(&symbol_short!("fee_cfg"))
+        }
+    }
+
+    /// In-memory stand-in for the CollateralRegistry
+    ///
+    /// Entries are registered with the `INVOICE` type unless retyped.
+    #[contract]
+    pub struct MockCollateralRegistry;
+
+    #[contractimpl]
+    impl MockCollateralRegistry {
+        pub fn register(env: Env, owner: Address, face_value: i128, expiry_ts: u64) -> u64 {
+            let id: u64 = env.storage().instance().get(&symbol_short!("next")).unwrap_or(1);
+            env.storage().instance().set(&symbol_short!("next"), &(id + 1));
+            let collateral = Collateral {
+                id,
+                owner,
+                face_value,
+                realized_value: 0,
+                expiry_ts,
+                metadata_hash: BytesN::from_array(&env, &[0u8; 32]),
+                registered_at: env.ledger().timestamp(),
+                last_valuation_ts: env.ledger().timestamp(),
+                locked: false,
+                lock_holder: None,
+                lock_ref: 0,
+            };
+            env.storage().persistent().set(&id, &collateral);
+            env.storage().persistent().set(&(symbol_short!("type"), id), &Symbol::new(&env, "INVOICE"));
+            id
+        }
+
+        pub fn get_collateral(env: Env, id: u64) -> Option<Collateral> {
+            env.storage().persistent().get(&id)
+        }
+
+        pub fn set_collateral_type(env: Env, id: u64, collateral_type: Symbol) {
+            env.storage().persistent().set(&(symbol_short!("type"), id), &collateral_type);
+        }
+
+        pub fn get_collateral_type(env: Env, id: u64) -> Option<Symbol> {
+            env.storage().persistent().get(&(symbol_short!("type"), id))
+        }
+
+        pub fn set_seized_value(env: Env, id: u64, value: i128) {
+            env.storage().persistent().set(&(symbol_short!("seized"), id), &value);
+        }
+
+        pub fn get_pledgeable_value(env: Env, id: u64) -> i128 {
+            let collateral: Collateral = env.storage().persistent().get(&id).unwrap();
+            let seized: i128 = env.storage().persistent().get(&(symbol_short!("seized"), id)).unwrap_or(0);
+            collateral.face_value - seized
+        }
+
+        /// Set the escrow manager recorded as the holder of escrow locks
+        pub fn set_escrow_manager(env: Env, escrow_manager: Address) {
+            env.storage().instance().set(&symbol_short!("mgr"), &escrow_manager);
+        }
+
+        pub fn lock_collateral(env: Env, id: u64, escrow_id: u64, _lender: Address) {
+            let mut collateral: Collateral = env.storage().persistent().get(&id).unwrap();
+            assert!(!collateral.locked, "collateral already locked");
+            collateral.locked = true;
+            collateral.lock_holder = env.storage().instance().get(&symbol_short!("mgr"));
+            collateral.lock_ref = escrow_id;
+            env.storage().persistent().set(&id, &collateral);
+        }
+
+        pub fn unlock_collateral(env: Env, id: u64, escrow_id: u64) {
+            let collateral: Collateral = env.storage().persistent().get(&id).unwrap();
+            assert!(collateral.lock_ref == escrow_id, "lock not held");
+            Self::release_collateral(env, id);
+        }
+
+        /// Unlock as the risk engine does when it closes a position
+        pub fn release_collateral(env: Env, id: u64) {
+            let mut collateral: Collateral = env.storage().persistent().get(&id).unwrap();
+            collateral.locked = false;
+            collateral.lock_holder = None;
+            collateral.lock_ref = 0;
+            env.storage().persistent().set(&id, &collateral);
+        }
+    }
+
+    /// Swap adapter that converts at a settable rate out of a pre-funded reserve
+    #[contract]
+    pub struct MockSwapAdapter;
+
+    #[contractimpl]
+    impl MockSwapAdapter {
+        pub fn set_rate(env: Env, rate_bps: i128) {
+            env.storage().instance().set(&symbol_short!("rate"), &rate_bps);
+        }
+
+        /// Withhold part of the output while still reporting the full amount
+        pub fn set_shortfall(env: Env, shortfall: i128) {
+            env.storage().instance().set(&symbol_short!("short"), &shortfall);
+        }
+
+        pub fn swap(
+            env: Env,
+            _asset_in: Address,
+            amount_in: i128,
+            asset_out: Address,
+            min_out: i128,
+            to: Address,
+        ) -> i128 {
+            let rate_bps: i128 = env.storage().instance().get(&symbol_short!("rate")).unwrap();
+            let amount_out = amount_in * rate_bps / 10000;
+            assert!(amount_out >= min_out, "slippage");
+            let shortfall: i128 = env.storage().instance().get(&symbol_short!("short")).unwrap_or(0);
+            token::Client::new(&env, &asset_out).transfer(&env.current_contract_address(), &to, &(amount_out - shortfall));
+            amount_out
+        }
+    }
+
+    /// Register a mock collateral registry with the vault
+    fn setup_registry(env: &Env, client: &StelloVaultContractClient) -> Address {
+        let registry_id = env.register(MockCollateralRegistry, ());
+        MockCollateralRegistryClient::new(env, &registry_id).set_escrow_manager(&client.address);
+        client.set_collateral_registry(&registry_id);
+        registry_id
+    }
+
+    #[test]
+    fn test_initialize() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+
+        let gov_token = Address::generate(&env);
+        client.initialize(&admin, &gov_token);
+
+        let admin_result = client.admin();
+        assert_eq!(admin_result, admin);
+
+        // Check default LTV via storage inspection
+        env.as_contract(&contract_id, || {
+            let max_ltv: u32 = env.storage().instance().get(&symbol_short!("max_ltv")).unwrap();
+            assert_eq!(max_ltv, 7000);
+        });
+    }
+
+    #[test]
+    fn test_create_and_release_escrow() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+
+        let gov_token = Address::generate(&env);
+        client.initialize(&admin, &gov_token);
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        // Whitelist oracle and collateral
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+        });
+
+        // Register collateral in the registry
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        // Create token for payment
+        let token_admin = Address::generate(&env);
+        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+        let token_address = token_contract.address();
+        let token = token::Client::new(&env, &token_address);
+        let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
+
+        token_admin_client.mint(&buyer, &10000);
+
+        let expiry = env.ledger().timestamp() + 1000;
+
+        // Collateral whose registry type is not whitelisted cannot back an escrow
+        registry.set_collateral_type(&token_id, &Symbol::new(&env, "WHEAT"));
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::create_escrow(
+                env.clone(), buyer.clone(), seller.clone(), lender.clone(), token_id, 7000,
+                token_address.clone(), oracle.clone(), symbol_short!("ship_del"), expiry, true
+            );
+            assert_eq!(result, Err(ContractError::AssetNotWhitelisted));
+        });
+        registry.set_collateral_type(&token_id, &asset_type);
+
+        let escrow_id = client.create_escrow(
+            &buyer,
+            &seller,
+            &lender,
+            &token_id,
+            &7000, // 70% LTV of 10000 = 7000 max
+            &token_address,
+            &oracle,
+            &symbol_short!("ship_del"),
+            &expiry,
+            &false
+        );
+
+        // The collateral is locked and cannot back a second deal
+        assert!(registry.get_collateral(&token_id).unwrap().locked);
+        assert!(client
+            .try_create_escrow(&buyer, &seller, &lender, &token_id, &1000, &token_address, &oracle, &symbol_short!("ship_del"), &expiry, &false)
+            .is_err());
+
+        // Nothing can be released before the buyer funds the escrow
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Pending);
+        assert!(client.try_release_funds(&escrow_id).is_err());
+
+        client.fund_escrow(&escrow_id);
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Active);
+        assert_eq!(token.balance(&contract_id), 7000);
+        assert_eq!(token.balance(&buyer), 3000);
+
+        client.release_funds(&escrow_id);
+
+        let escrow = client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+        assert_eq!(token.balance(&seller), 7000);
+        assert!(!registry.get_collateral(&token_id).unwrap().locked);
+
+        // Once half the collateral has been seized only the remainder counts towards LTV
+        registry.set_seized_value(&token_id, &5000);
+        assert!(client
+            .try_create_escrow(&buyer, &seller, &lender, &token_id, &3600, &token_address, &oracle, &symbol_short!("ship_del"), &expiry, &false)
+            .is_err());
+        client.create_escrow(&buyer, &seller, &lender, &token_id, &3500, &token_address, &oracle, &symbol_short!("ship_del"), &expiry, &false);
+    }
+
+    #[test]
+    fn test_escrow_expiry() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+
+        let gov_token = Address::generate(&env);
+        client.initialize(&admin, &gov_token);
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        // Whitelist oracle and collateral
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+        });
+
+        // Register collateral in the registry
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_admin = Address::generate(&env);
+        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
+        let token_address = token_contract.address();
+        let token = token::Client::new(&env, &token_address);
+        let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
+
+        token_admin_client.mint(&buyer, &10000);
+
+        let expiry = env.ledger().timestamp() + 100;
+        let escrow_id = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &true
+        );
+        assert_eq!(token.balance(&contract_id), 500);
+
+        env.ledger().set_timestamp(expiry + 1);
+
+        client.expire_escrow(&escrow_id);
+
+        let escrow = client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Cancelled);
+        assert_eq!(token.balance(&buyer), 10000);
+
+        // Collateral the risk engine released and the owner pledged again is
+        // not freed when the original escrow expires
+        let expiry = env.ledger().timestamp() + 100;
+        let stale = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &false
+        );
+        registry.release_collateral(&token_id);
+        let pledged = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &(expiry + 1000), &false
+        );
+        env.ledger().set_timestamp(expiry + 1);
+        client.expire_escrow(&stale);
+        let collateral = registry.get_collateral(&token_id).unwrap();
+        assert!(collateral.locked);
+        assert_eq!(collateral.lock_ref, pledged);
+
+        // Without a registry the collateral can't be unlocked, so the escrow stays open
+        env.as_contract(&contract_id, || {
+            env.storage().instance().remove(&symbol_short!("coll_reg"));
+            let result = StelloVaultContract::cancel_escrow(env.clone(), pledged, buyer.clone());
+            assert_eq!(result, Err(ContractError::RegistryNotConfigured));
+        });
+    }
+
+    #[test]
+    fn test_cancel_unfunded_escrow() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let token = token::Client::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+        let expiry = env.ledger().timestamp() + 100;
+        let first = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &false
+        );
+        let second_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+        let second = client.create_escrow(
+            &buyer, &seller, &lender, &second_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &false
+        );
+
+        // Only the parties to the deal can cancel
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::cancel_escrow(env.clone(), first, lender.clone());
+            assert_eq!(result, Err(ContractError::Unauthorized));
+        });
+
+        // Either party may walk away before funding
+        client.cancel_escrow(&first, &seller);
+        client.cancel_escrow(&second, &buyer);
+        assert_eq!(client.get_escrow(&first).unwrap().status, EscrowStatus::Cancelled);
+        assert_eq!(client.get_escrow(&second).unwrap().status, EscrowStatus::Cancelled);
+        assert!(client.try_fund_escrow(&first).is_err());
+        assert!(!registry.get_collateral(&token_id).unwrap().locked);
+        assert!(!registry.get_collateral(&second_id).unwrap().locked);
+
+        // Funded escrows can no longer be cancelled, and late funding is refused
+        let funded = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &true
+        );
+        assert!(client.try_cancel_escrow(&funded, &buyer).is_err());
+
+        let late = client.create_escrow(
+            &buyer, &seller, &lender, &second_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &false
+        );
+        env.ledger().set_timestamp(expiry + 1);
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::fund_escrow(env.clone(), late);
+            assert_eq!(result, Err(ContractError::EscrowExpired));
+        });
+
+        assert_eq!(token.balance(&contract_id), 500);
+        assert_eq!(token.balance(&buyer), 500);
+
+        // The unfunded escrow expires without a refund and frees its collateral
+        client.expire_escrow(&late);
+        assert_eq!(client.get_escrow(&late).unwrap().status, EscrowStatus::Cancelled);
+        assert!(!registry.get_collateral(&second_id).unwrap().locked);
+        assert_eq!(token.balance(&contract_id), 500);
+        assert_eq!(token.balance(&buyer), 500);
+    }
+
+    #[test]
+    fn test_milestone_releases() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+
+        let asset_type = Symbol::new(&env, "INVOICE");
         env.as_contract(&contract_id, || {
             env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
             env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
         });
 
-        // Create collateral
-        let token_id = client.tokenize_collateral(
-            &buyer,
-            &asset_type,
-            &10000,
-            &Symbol::new(&env, "META"),
-            &100
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let token = token::Client::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &2000);
+
+        // Deposit on shipment, balance on delivery, holdback after inspection
+        let milestones = vec![
+            &env,
+            Milestone { event_type: 1, share_bps: 3000 },
+            Milestone { event_type: 2, share_bps: 5000 },
+            Milestone { event_type: 3, share_bps: 2000 },
+        ];
+
+        // Shares must cover the whole amount
+        let short = vec![&env, Milestone { event_type: 1, share_bps: 9000 }];
+        let expiry = env.ledger().timestamp() + 1000;
+        assert!(client
+            .try_create_milestone_escrow(&buyer, &seller, &lender, &token_id, &1001, &token_address, &oracle, &expiry, &short, &true)
+            .is_err());
+
+        let escrow_id = client.create_milestone_escrow(
+            &buyer, &seller, &lender, &token_id, &1001, &token_address, &oracle, &expiry, &milestones, &true
         );
+        assert_eq!(client.get_milestones(&escrow_id), milestones);
 
-        let token_admin = Address::generate(&env);
-        let token_contract = env.register_stellar_asset_contract_v2(token_admin);
-        let token_address = token_contract.address();
+        // Delivery cannot be paid before shipment
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::release_milestone(env.clone(), escrow_id, 2);
+            assert_eq!(result, Err(ContractError::MilestoneOutOfOrder));
+        });
+
+        assert_eq!(client.release_milestone(&escrow_id, &1), 300);
+        assert_eq!(client.release_milestone(&escrow_id, &2), 500);
+
+        let escrow = client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Active);
+        assert_eq!(escrow.released_amount, 800);
+        assert_eq!(client.get_next_milestone(&escrow_id), 2);
+
+        // The holdback takes the rounding remainder and completes the escrow
+        assert_eq!(client.release_milestone(&escrow_id, &3), 201);
+        let escrow = client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+        assert_eq!(escrow.released_amount, 1001);
+        assert_eq!(token.balance(&seller), 1001);
+        assert!(client.try_release_milestone(&escrow_id, &3).is_err());
+
+        // An expired escrow refunds only the tranches still held
+        let expiring = client.create_milestone_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &expiry, &milestones, &true
+        );
+        client.release_milestone(&expiring, &1);
+        env.ledger().set_timestamp(expiry + 1);
+        client.expire_escrow(&expiring);
+
+        assert_eq!(client.get_escrow(&expiring).unwrap().status, EscrowStatus::Cancelled);
+        assert_eq!(token.balance(&seller), 1001 + 150);
+        assert_eq!(token.balance(&buyer), 2000 - 1001 - 150);
+        assert_eq!(token.balance(&contract_id), 0);
+    }
+
+    #[test]
+    fn test_milestone_release_condition() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let adapter_id = env.register(MockOracleAdapter, ());
+        let adapter = MockOracleAdapterClient::new(&env, &adapter_id);
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), adapter_id.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), Symbol::new(&env, "INVOICE")), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
         let token = token::Client::new(&env, &token_address);
-        let token_admin_client = token::StellarAssetClient::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+        let milestones = vec![
+            &env,
+            Milestone { event_type: 1, share_bps: 4000 },
+            Milestone { event_type: 2, share_bps: 6000 },
+        ];
+        let escrow_id = client.create_milestone_escrow(
+            &buyer, &seller, &lender, &token_id, &1000, &token_address, &adapter_id,
+            &(env.ledger().timestamp() + 1000), &milestones, &true
+        );
 
-        token_admin_client.mint(&buyer, &10000);
+        // The condition must follow the escrow's milestones
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::set_release_condition(env.clone(), escrow_id, vec![&env, 2u32, 1u32], 1);
+            assert_eq!(result, Err(ContractError::InvalidMilestones));
+        });
 
-        let expiry = env.ledger().timestamp() + 100;
+        client.set_release_condition(&escrow_id, &vec![&env, 1u32, 2u32], &1u32);
+
+        // Milestone escrows register a per-milestone condition, not a full release
+        env.as_contract(&adapter_id, || {
+            assert!(!env.storage().instance().has(&symbol_short!("cond")));
+            let (escrow_contract, _, escrow_ref, event_types, _): (Address, Bytes, u64, Vec<u32>, u32) =
+                env.storage().instance().get(&symbol_short!("ms_cond")).unwrap();
+            assert_eq!(escrow_contract, contract_id);
+            assert_eq!(escrow_ref, escrow_id);
+            assert_eq!(event_types, vec![&env, 1u32, 2u32]);
+        });
+
+        // Each confirmed milestone pays only its own tranche
+        assert_eq!(adapter.fire_milestone(&1), 400);
+        assert_eq!(token.balance(&seller), 400);
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Active);
+
+        assert_eq!(adapter.fire_milestone(&2), 600);
+        assert_eq!(token.balance(&seller), 1000);
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+    }
+
+    #[test]
+    fn test_approval_policy_release() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        let inspector = Address::generate(&env);
+
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let token = token::Client::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &1000);
+
+        let expiry = env.ledger().timestamp() + 1000;
         let escrow_id = client.create_escrow(
-            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry
+            &buyer, &seller, &lender, &token_id, &1000, &token_address, &oracle, &symbol_short!("cond"), &expiry, &true
         );
 
-        env.ledger().set_timestamp(expiry + 1);
+        // Thresholds outside 1..=N and duplicate signers are rejected
+        let signers = vec![&env, buyer.clone(), lender.clone(), inspector.clone()];
+        assert!(client.try_set_approval_policy(&escrow_id, &signers, &4).is_err());
+        assert!(client
+            .try_set_approval_policy(&escrow_id, &vec![&env, buyer.clone(), buyer.clone()], &1)
+            .is_err());
 
-        client.expire_escrow(&escrow_id);
+        client.set_approval_policy(&escrow_id, &signers, &2);
+        assert!(env.auths().iter().any(|(address, _)| *address == lender));
+        assert_eq!(client.get_approval_policy(&escrow_id).unwrap().threshold, 2);
+        assert!(client.try_set_approval_policy(&escrow_id, &signers, &1).is_err());
 
-        let escrow = client.get_escrow(&escrow_id).unwrap();
-        assert_eq!(escrow.status, EscrowStatus::Cancelled);
+        // The seller is not a signer under this policy
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::approve_release(env.clone(), escrow_id, seller.clone());
+            assert_eq!(result, Err(ContractError::Unauthorized));
+        });
+
+        assert!(!client.approve_release(&escrow_id, &inspector));
+        assert!(client.has_approved(&escrow_id, &inspector));
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::approve_release(env.clone(), escrow_id, inspector.clone());
+            assert_eq!(result, Err(ContractError::AlreadyApproved));
+        });
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Active);
+
+        // The second approval meets the threshold and releases automatically
+        assert!(client.approve_release(&escrow_id, &lender));
+        assert_eq!(client.get_approval_count(&escrow_id), 2);
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+        assert_eq!(token.balance(&seller), 1000);
+
+        // Without a policy approvals are refused, and the oracle path still works
+        let oracle_escrow = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &500, &token_address, &oracle, &symbol_short!("cond"), &expiry, &false
+        );
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::approve_release(env.clone(), oracle_escrow, buyer.clone());
+            assert_eq!(result, Err(ContractError::ApprovalPolicyNotFound));
+        });
+    }
+
+    #[test]
+    fn test_dispute_resolution() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        let arbiter = Address::generate(&env);
+
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+            env.storage().instance().set(&symbol_short!("arbiter"), &arbiter);
+        });
+        assert_eq!(client.get_arbiter(), Some(arbiter.clone()));
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let token = token::Client::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &2000);
+
+        let expiry = env.ledger().timestamp() + 1000;
+        let split = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &1000, &token_address, &oracle, &symbol_short!("cond"), &expiry, &true
+        );
+        let full_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+        let full = client.create_escrow(
+            &buyer, &seller, &lender, &full_id, &1000, &token_address, &oracle, &symbol_short!("cond"), &expiry, &true
+        );
+
+        client.dispute_escrow(&split, &seller);
+        client.dispute_escrow(&full, &buyer);
+
+        // Parties file evidence hashes during the window; outsiders cannot
+        let buyer_hash = BytesN::from_array(&env, &[1u8; 32]);
+        let seller_hash = BytesN::from_array(&env, &[2u8; 32]);
+        client.submit_evidence(&split, &buyer, &buyer_hash);
+        client.submit_evidence(&split, &seller, &BytesN::from_array(&env, &[9u8; 32]));
+        client.submit_evidence(&split, &seller, &seller_hash);
+        assert!(client.try_submit_evidence(&split, &arbiter, &buyer_hash).is_err());
+
+        let evidence = client.get_evidence(&split);
+        assert_eq!(evidence.len(), 2);
+        assert_eq!(evidence.get(0).unwrap().submitter, buyer);
+        assert_eq!(evidence.get(1).unwrap().evidence_hash, seller_hash);
+
+        // No ruling while evidence can still arrive
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::resolve_dispute(env.clone(), split, 6000);
+            assert_eq!(result, Err(ContractError::EvidenceWindowOpen));
+        });
+
+        env.ledger().set_timestamp(env.ledger().timestamp() + DEFAULT_EVIDENCE_WINDOW + 1);
+
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::submit_evidence(env.clone(), split, buyer.clone(), buyer_hash.clone());
+            assert_eq!(result, Err(ContractError::EvidenceWindowClosed));
+        });
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::resolve_dispute(env.clone(), split, 10001);
+            assert_eq!(result, Err(ContractError::InvalidRuling));
+        });
+
+        // The seller's share pays the release fee; the buyer's refund does not
+        let treasury = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            let config = FeeConfig {
+                origination_bps: 0,
+                escrow_release_bps: 1000,
+                liquidation_share_bps: 0,
+                treasury: treasury.clone(),
+            };
+            env.storage().instance().set(&symbol_short!("fee_cfg"), &config);
+        });
+
+        // 60/40 split keeps the collateral with its owner
+        client.resolve_dispute(&split, &6000);
+        assert_eq!(client.get_escrow(&split).unwrap().status, EscrowStatus::Resolved);
+        assert_eq!(token.balance(&seller), 540);
+        assert_eq!(token.balance(&treasury), 60);
+        assert_eq!(token.balance(&buyer), 400);
+        let collateral = registry.get_collateral(&token_id).unwrap();
+        assert_eq!(collateral.owner, buyer);
+        assert!(!collateral.locked);
+        assert!(client.try_resolve_dispute(&split, &6000).is_err());
+
+        // A full ruling for the seller pays out but leaves the collateral with its owner
+        client.resolve_dispute(&full, &10000);
+        assert_eq!(token.balance(&seller), 1440);
+        assert_eq!(token.balance(&treasury), 160);
+        assert_eq!(token.balance(&contract_id), 0);
+        let collateral = registry.get_collateral(&full_id).unwrap();
+        assert_eq!(collateral.owner, buyer);
+        assert!(!collateral.locked);
     }
 
     #[test]
@@ -741,10 +2444,10 @@ mod test {
 
         // Create a separate token contract for testing
         let gov_token_admin = Address::generate(&env);
-        let gov_token_id = env.register_stellar_asset_contract(gov_token_admin.clone());
+        let gov_token_id = env.register_stellar_asset_contract_v2(gov_token_admin.clone()).address();
 
         token::StellarAssetClient::new(&env, &gov_token_id).mint(&user1, &1000);
-        token::StellarAssetClient::new(&env, &gov_token_id).mint(&user2, &1000);
+        token::StellarAssetClient::new(&env, &gov_token_id).mint(&user2, &10000);
 
         client.initialize(&admin, &gov_token_id);
 
@@ -764,13 +2467,13 @@ mod test {
         // User 1 votes with weight 100 -> sqrt(100) = 10 votes
         client.vote(&user1, &proposal_id, &100);
 
-        // User 2 votes with weight 400 -> sqrt(400) = 20 votes
-        client.vote(&user2, &proposal_id, &400);
+        // User 2 votes with weight 8100 -> sqrt(8100) = 90 votes
+        client.vote(&user2, &proposal_id, &8100);
 
         // Check details via storage inspection
         env.as_contract(&contract_id, || {
             let proposal: Proposal = env.storage().persistent().get(&(symbol_short!("prop"), proposal_id)).unwrap();
-            assert_eq!(proposal.vote_count, 30);
+            assert_eq!(proposal.vote_count, 100);
         });
 
         // Advance time past vote period
@@ -788,4 +2491,279 @@ mod test {
             assert!(proposal_updated.executed);
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_oracle_adapter_release_condition() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let adapter_id = env.register(MockOracleAdapter, ());
+        let adapter = MockOracleAdapterClient::new(&env, &adapter_id);
+
+        // Whitelist the adapter as oracle and collateral
+        let asset_type = Symbol::new(&env, "INVOICE");
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), adapter_id.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), asset_type.clone()), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let token_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let token_contract = env.register_stellar_asset_contract_v2(Address::generate(&env));
+        let token_address = token_contract.address();
+        let token = token::Client::new(&env, &token_address);
+        token::StellarAssetClient::new(&env, &token_address).mint(&buyer, &5000);
+
+        let expiry = env.ledger().timestamp() + 1000;
+        let escrow_id = client.create_escrow(
+            &buyer, &seller, &lender, &token_id, &5000, &token_address, &adapter_id, &symbol_short!("ship_del"), &expiry, &true
+        );
+
+        client.set_release_condition(&escrow_id, &vec![&env, 1u32, 2u32], &2u32);
+
+        // The adapter registered this contract and escrow for the callback
+        env.as_contract(&adapter_id, || {
+            let (escrow_contract, oracle_escrow_id, escrow_ref, event_types, quorum): (Address, Bytes, u64, Vec<u32>, u32) =
+                env.storage().instance().get(&symbol_short!("cond")).unwrap();
+            assert_eq!(escrow_contract, contract_id);
+            assert_eq!(oracle_escrow_id, Bytes::from_slice(&env, &escrow_id.to_be_bytes()));
+            assert_eq!(escrow_ref, escrow_id);
+            assert_eq!(event_types, vec![&env, 1u32, 2u32]);
+            assert_eq!(quorum, 2);
+        });
+
+        // Adapter callback releases funds to the seller
+        adapter.fire();
+
+        let escrow = client.get_escrow(&escrow_id).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Released);
+        assert_eq!(token.balance(&seller), 5000);
+    }
+
+    #[test]
+    fn test_governance_updates_risk_parameters() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let voter = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+
+        let gov_token_admin = Address::generate(&env);
+        let gov_token_id = env.register_stellar_asset_contract_v2(gov_token_admin.clone()).address();
+        token::StellarAssetClient::new(&env, &gov_token_id).mint(&voter, &10000);
+        client.initialize(&admin, &gov_token_id);
+
+        // The vault is the risk engine's governance address
+        let risk_engine = env.register(MockRiskAssessment, ());
+        let risk_client = MockRiskAssessmentClient::new(&env, &risk_engine);
+        risk_client.set_governance(&contract_id);
+        client.set_risk_engine(&risk_engine);
+        assert_eq!(client.get_risk_engine(), Some(risk_engine.clone()));
+
+        let params = RiskParameters {
+            liquidation_threshold: 7500,
+            liquidation_penalty: 600,
+            min_health_factor: 11000,
+            max_liquidation_ratio: 4000,
+            grace_period: 7200,
+            liquidator_bonus: 600,
+        };
+        let action = GovernanceAction::UpdateRiskParameters(params.clone());
+
+        let proposal_id = client.propose(
+            &voter,
+            &Symbol::new(&env, "RISK_UP"),
+            &Symbol::new(&env, "Tighten_risk"),
+            &action,
+            &1000
+        );
+
+        // sqrt(10000) = 100 votes meets the default quorum
+        client.vote(&voter, &proposal_id, &10000);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+
+        assert_eq!(risk_client.get_pending(), None);
+        client.execute_proposal(&proposal_id);
+        assert_eq!(risk_client.get_pending(), Some(params));
+    }
+
+    #[test]
+    fn test_cross_asset_payout() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &Address::generate(&env));
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), Symbol::new(&env, "INVOICE")), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let expiry = env.ledger().timestamp() + 1000;
+        let first_collateral = registry.register(&buyer, &10000, &(expiry + 86400));
+        let second_collateral = registry.register(&buyer, &10000, &(expiry + 86400));
+
+        let usdc = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let local = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let local_token = token::Client::new(&env, &local);
+        token::StellarAssetClient::new(&env, &usdc).mint(&buyer, &2000);
+
+        let escrow_id = client.create_escrow(
+            &buyer, &seller, &lender, &first_collateral, &1000, &usdc, &oracle,
+            &symbol_short!("delivery"), &expiry, &true
+        );
+
+        // Cross-asset payout needs a swap adapter
+        env.as_contract(&contract_id, || {
+            let result = StelloVaultContract::set_payout_terms(env.clone(), escrow_id, local.clone(), 1900);
+            assert_eq!(result, Err(ContractError::SwapAdapterNotConfigured));
+        });
+
+        let adapter_id = env.register(MockSwapAdapter, ());
+        let adapter = MockSwapAdapterClient::new(&env, &adapter_id);
+        adapter.set_rate(&20000);
+        token::StellarAssetClient::new(&env, &local).mint(&adapter_id, &10000);
+        client.set_swap_adapter(&adapter_id);
+
+        client.set_payout_terms(&escrow_id, &local, &1900);
+        assert_eq!(client.get_payout_terms(&escrow_id).unwrap().min_receive, 1900);
+
+        client.release_funds(&escrow_id);
+        assert_eq!(local_token.balance(&seller), 2000);
+        assert_eq!(token::Client::new(&env, &usdc).balance(&seller), 0);
+        assert_eq!(client.get_escrow(&escrow_id).unwrap().status, EscrowStatus::Released);
+
+        // A rate below the seller's minimum fails the release and keeps the funds escrowed
+        let second = client.create_escrow(
+            &buyer, &seller, &lender, &second_collateral, &1000, &usdc, &oracle,
+            &symbol_short!("delivery"), &expiry, &true
+        );
+        client.set_payout_terms(&second, &local, &1900);
+        adapter.set_rate(&18000);
+        assert!(client.try_release_funds(&second).is_err());
+
+        let escrow = client.get_escrow(&second).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Active);
+        assert_eq!(token::Client::new(&env, &usdc).balance(&contract_id), 1000);
+
+        // An adapter that under-delivers but reports the full amount is caught
+        adapter.set_rate(&20000);
+        adapter.set_shortfall(&200);
+        assert!(client.try_release_funds(&second).is_err());
+        assert_eq!(local_token.balance(&seller), 2000);
+        assert_eq!(client.get_escrow(&second).unwrap().status, EscrowStatus::Active);
+        assert_eq!(token::Client::new(&env, &usdc).balance(&contract_id), 1000);
+        // With a 10% release fee the minimum applies to the 900 actually swapped
+        adapter.set_shortfall(&0);
+        let treasury = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            let config = FeeConfig {
+                origination_bps: 0,
+                escrow_release_bps: 1000,
+                liquidation_share_bps: 0,
+                treasury: treasury.clone(),
+            };
+            env.storage().instance().set(&symbol_short!("fee_cfg"), &config);
+        });
+        client.release_funds(&second);
+        assert_eq!(token::Client::new(&env, &usdc).balance(&treasury), 100);
+        assert_eq!(local_token.balance(&seller), 2000 + 1800);
+        assert_eq!(client.get_escrow(&second).unwrap().status, EscrowStatus::Released);
+    }
+
+    #[test]
+    fn test_protocol_fees() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let voter = Address::generate(&env);
+        let contract_id = env.register(StelloVaultContract, ());
+        let client = StelloVaultContractClient::new(&env, &contract_id);
+
+        let gov_token_id = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        // Votes lock the voter's tokens, one proposal per action
+        token::StellarAssetClient::new(&env, &gov_token_id).mint(&voter, &20000);
+        client.initialize(&admin, &gov_token_id);
+
+        let buyer = Address::generate(&env);
+        let seller = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        let treasury = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&(symbol_short!("w_orc"), oracle.clone()), &true);
+            env.storage().persistent().set(&(symbol_short!("w_col"), Symbol::new(&env, "INVOICE")), &true);
+        });
+
+        let registry = MockCollateralRegistryClient::new(&env, &setup_registry(&env, &client));
+        let collateral_id = registry.register(&buyer, &10000, &(env.ledger().timestamp() + 86400));
+
+        let asset = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+        let token = token::Client::new(&env, &asset);
+        token::StellarAssetClient::new(&env, &asset).mint(&buyer, &1000);
+
+        let config = FeeConfig {
+            origination_bps: 100,
+            escrow_release_bps: 50,
+            liquidation_share_bps: 2000,
+            treasury: treasury.clone(),
+        };
+
+        // The vault is the risk engine's governance address
+        let risk_engine = env.register(MockRiskAssessment, ());
+        let risk_client = MockRiskAssessmentClient::new(&env, &risk_engine);
+        risk_client.set_governance(&contract_id);
+        client.set_risk_engine(&risk_engine);
+
+        let actions = vec![
+            &env,
+            GovernanceAction::UpdateFeeConfig(config.clone()),
+            GovernanceAction::UpdateRiskFeeConfig(config.clone()),
+        ];
+        for action in actions.iter() {
+            let proposal_id = client.propose(
+                &voter,
+                &Symbol::new(&env, "FEES"),
+                &Symbol::new(&env, "Enable_fees"),
+                &action,
+                &1000
+            );
+            client.vote(&voter, &proposal_id, &10000);
+            env.ledger().set_timestamp(env.ledger().timestamp() + 1001);
+            client.execute_proposal(&proposal_id);
+        }
+
+        assert_eq!(client.get_fee_config(), Some(config.clone()));
+        assert_eq!(risk_client.get_fee_config(), Some(config));
+
+        let escrow_id = client.create_escrow(
+            &buyer, &seller, &Address::generate(&env), &collateral_id, &1000, &asset, &oracle,
+            &symbol_short!("delivery"), &(env.ledger().timestamp() + 1000), &true
+        );
+        client.release_funds(&escrow_id);
+
+        // 0.5% of the release goes to the treasury
+        assert_eq!(token.balance(&treasury), 5);
+        assert_eq!(token.balance(&seller), 995);
+        assert_eq!(client.get_accrued_fees(&asset), 5);
+    }
+}
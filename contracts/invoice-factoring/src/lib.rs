@@ -0,0 +1,774 @@
+//! Invoice Factoring Contract for StelloVault
+//!
+//! This contract lets a seller assign an invoice registered in the
+//! CollateralRegistry to a factor in exchange for an advance. The buyer owing
+//! the invoice (the debtor) is notified and acknowledges the assignment
+//! on-chain, after which its payments are routed to the factor until the
+//! advance and fee are covered, with the remaining reserve paid to the seller.
+
+#![no_std]
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env,
+};
+
+/// Contract errors
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Unauthorized = 1,
+    AlreadyInitialized = 2,
+    AssignmentNotFound = 3,
+    InvalidAmount = 4,
+    InvalidTerms = 5,
+    CollateralNotFound = 6,
+    NotCollateralOwner = 7,
+    CollateralLocked = 8,
+    CollateralExpired = 9,
+    InvalidStatus = 10,
+    NotAcknowledged = 11,
+    NotOverdue = 12,
+    MathOverflow = 13,
+}
+
+impl From<soroban_sdk::Error> for ContractError {
+    fn from(_: soroban_sdk::Error) -> Self {
+        ContractError::Unauthorized
+    }
+}
+
+impl From<&ContractError> for soroban_sdk::Error {
+    fn from(err: &ContractError) -> Self {
+        soroban_sdk::Error::from_contract_error(*err as u32)
+    }
+}
+
+/// Assignment lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssignmentStatus {
+    /// Advance paid, waiting for the debtor to acknowledge
+    Assigned = 0,
+    /// Debtor acknowledged; payments are routed to the factor
+    Acknowledged = 1,
+    /// Invoice paid in full and the reserve released
+    Settled = 2,
+    /// Invoice unpaid at maturity and written off by the factor
+    Defaulted = 3,
+}
+
+/// Invoice assignment data structure
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    pub id: u64,
+    pub collateral_id: u64,
+    pub seller: Address,
+    pub factor: Address,
+    pub debtor: Address,
+    pub asset: Address,
+    pub face_value: i128,
+    pub advance_amount: i128,
+    pub fee_amount: i128,
+    pub paid_amount: i128,
+    pub due_ts: u64, // Collateral expiry
+    pub status: AssignmentStatus,
+    pub assigned_at: u64,
+    pub acknowledged_at: u64,
+}
+
+/// Registry collateral (mirrors CollateralRegistry)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Collateral {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub lock_holder: Option<Address>,
+    pub lock_ref: u64,
+}
+
+/// CollateralRegistry interface
+#[contractclient(name = "CollateralRegistryClient")]
+pub trait CollateralRegistry {
+    fn get_collateral(env: Env, id: u64) -> Option<Collateral>;
+    fn assign_collateral(env: Env, id: u64, assignment_id: u64, assignee: Address);
+    fn release_assignment(env: Env, id: u64, assignment_id: u64);
+    fn settle_assignment(env: Env, id: u64, assignment_id: u64);
+}
+
+/// Default advance rate (80% of face value)
+const DEFAULT_ADVANCE_RATE_BPS: u32 = 8000;
+
+/// Default factoring fee (2% of face value)
+const DEFAULT_FEE_BPS: u32 = 200;
+
+#[contract]
+pub struct InvoiceFactoringContract;
+
+#[contractimpl]
+impl InvoiceFactoringContract {
+    /// Initialize the contract with admin, governance and collateral registry
+    /// addresses
+    ///
+    /// This contract must be set as the registry's factoring manager.
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        governance: Address,
+        registry: Address,
+    ) -> Result<(), ContractError> {
+        if env.storage().instance().has(&symbol_short!("admin")) {
+            return Err(ContractError::AlreadyInitialized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("coll_reg"), &registry);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("adv_bps"), &DEFAULT_ADVANCE_RATE_BPS);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("fee_bps"), &DEFAULT_FEE_BPS);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("asg_next"), &1u64);
+
+        env.events()
+            .publish((symbol_short!("fac_init"),), (admin, registry));
+        Ok(())
+    }
+
+    /// Update the advance rate and fee applied to new assignments (governance only)
+    ///
+    /// Existing assignments keep the terms they were created with.
+    ///
+    /// # Arguments
+    /// * `advance_rate_bps` - Share of face value advanced to the seller
+    /// * `fee_bps` - Share of face value kept by the factor as its fee
+    ///
+    /// # Events
+    /// Emits `fac_term` with (advance_rate_bps, fee_bps)
+    pub fn set_terms(env: Env, advance_rate_bps: u32, fee_bps: u32) -> Result<(), ContractError> {
+        let governance = Self::governance(env.clone())?;
+        governance.require_auth();
+
+        if advance_rate_bps == 0 || advance_rate_bps + fee_bps > 10000 {
+            return Err(ContractError::InvalidTerms);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("adv_bps"), &advance_rate_bps);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("fee_bps"), &fee_bps);
+
+        env.events()
+            .publish((symbol_short!("fac_term"),), (advance_rate_bps, fee_bps));
+        Ok(())
+    }
+
+    /// Assign an invoice to a factor against an advance
+    ///
+    /// The invoice collateral is locked in the registry and its ownership
+    /// passes to the factor, who pays the advance to the seller in `asset`.
+    /// Both the seller and the factor must authorize.
+    ///
+    /// # Arguments
+    /// * `seller` - Current owner of the invoice collateral
+    /// * `factor` - Party buying the invoice
+    /// * `collateral_id` - Registry ID of the invoice
+    /// * `debtor` - Buyer owing the invoice
+    /// * `asset` - Settlement asset
+    ///
+    /// # Events
+    /// Emits `fac_asgn` with (assignment_id, collateral_id, factor, advance_amount)
+    /// and `fac_ntfy` with (assignment_id, debtor) to notify the debtor
+    pub fn assign_invoice(
+        env: Env,
+        seller: Address,
+        factor: Address,
+        collateral_id: u64,
+        debtor: Address,
+        asset: Address,
+    ) -> Result<u64, ContractError> {
+        seller.require_auth();
+        factor.require_auth();
+
+        let registry = Self::registry_client(&env)?;
+        let collateral = registry
+            .get_collateral(&collateral_id)
+            .ok_or(ContractError::CollateralNotFound)?;
+
+        if collateral.owner != seller {
+            return Err(ContractError::NotCollateralOwner);
+        }
+        if collateral.locked {
+            return Err(ContractError::CollateralLocked);
+        }
+        if collateral.expiry_ts <= env.ledger().timestamp() {
+            return Err(ContractError::CollateralExpired);
+        }
+        if collateral.face_value <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let (advance_rate_bps, fee_bps) = Self::get_terms(env.clone());
+        let advance_amount = collateral
+            .face_value
+            .checked_mul(advance_rate_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+        let fee_amount = collateral
+            .face_value
+            .checked_mul(fee_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+
+        let assignment_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("asg_next"))
+            .unwrap_or(1);
+
+        registry.assign_collateral(&collateral_id, &assignment_id, &factor);
+
+        let token_client = token::Client::new(&env, &asset);
+        token_client.transfer(&factor, &seller, &advance_amount);
+
+        let assignment = Assignment {
+            id: assignment_id,
+            collateral_id,
+            seller,
+            factor: factor.clone(),
+            debtor: debtor.clone(),
+            asset,
+            face_value: collateral.face_value,
+            advance_amount,
+            fee_amount,
+            paid_amount: 0,
+            due_ts: collateral.expiry_ts,
+            status: AssignmentStatus::Assigned,
+            assigned_at: env.ledger().timestamp(),
+            acknowledged_at: 0,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("asgn"), assignment_id), &assignment);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("asg_next"), &(assignment_id + 1));
+
+        env.events().publish(
+            (symbol_short!("fac_asgn"),),
+            (assignment_id, collateral_id, factor, advance_amount),
+        );
+        env.events()
+            .publish((symbol_short!("fac_ntfy"),), (assignment_id, debtor));
+
+        Ok(assignment_id)
+    }
+
+    /// Acknowledge notice of assignment (debtor only)
+    ///
+    /// From this point the debtor can only discharge the invoice by paying
+    /// through this contract.
+    ///
+    /// # Events
+    /// Emits `fac_ack` with (assignment_id, debtor)
+    pub fn acknowledge_assignment(env: Env, assignment_id: u64) -> Result<(), ContractError> {
+        let mut assignment = Self::load_assignment(&env, assignment_id)?;
+
+        assignment.debtor.require_auth();
+
+        if assignment.status != AssignmentStatus::Assigned {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        assignment.status = AssignmentStatus::Acknowledged;
+        assignment.acknowledged_at = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("asgn"), assignment_id), &assignment);
+
+        env.events().publish(
+            (symbol_short!("fac_ack"),),
+            (assignment_id, assignment.debtor),
+        );
+        Ok(())
+    }
+
+    /// Pay an acknowledged invoice (debtor only)
+    ///
+    /// Payments go to the factor until the advance and fee are recovered;
+    /// anything above that is the seller's reserve and goes to the seller.
+    /// Payments beyond the outstanding amount are capped. Once the invoice
+    /// is paid in full the assignment settles and the collateral is
+    /// released in the registry.
+    ///
+    /// # Arguments
+    /// * `assignment_id` - The assignment ID
+    /// * `amount` - Amount to pay
+    ///
+    /// # Returns
+    /// The amount still outstanding
+    ///
+    /// # Events
+    /// Emits `fac_pay` with (assignment_id, to_factor, to_seller) and, on
+    /// full payment, `fac_setl` with (assignment_id, reserve)
+    pub fn pay_invoice(env: Env, assignment_id: u64, amount: i128) -> Result<i128, ContractError> {
+        let mut assignment = Self::load_assignment(&env, assignment_id)?;
+
+        assignment.debtor.require_auth();
+
+        if assignment.status != AssignmentStatus::Acknowledged {
+            return Err(if assignment.status == AssignmentStatus::Assigned {
+                ContractError::NotAcknowledged
+            } else {
+                ContractError::InvalidStatus
+            });
+        }
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let outstanding = assignment.face_value - assignment.paid_amount;
+        let payment = amount.min(outstanding);
+
+        let factor_share = assignment.advance_amount + assignment.fee_amount;
+        let factor_due = (factor_share - assignment.paid_amount).max(0);
+        let to_factor = payment.min(factor_due);
+        let to_seller = payment - to_factor;
+
+        let token_client = token::Client::new(&env, &assignment.asset);
+        if to_factor > 0 {
+            token_client.transfer(&assignment.debtor, &assignment.factor, &to_factor);
+        }
+        if to_seller > 0 {
+            token_client.transfer(&assignment.debtor, &assignment.seller, &to_seller);
+        }
+
+        assignment.paid_amount += payment;
+
+        env.events().publish(
+            (symbol_short!("fac_pay"),),
+            (assignment_id, to_factor, to_seller),
+        );
+
+        if assignment.paid_amount == assignment.face_value {
+            assignment.status = AssignmentStatus::Settled;
+            // A paid invoice is realized and cannot be pledged again
+            Self::registry_client(&env)?
+                .settle_assignment(&assignment.collateral_id, &assignment_id);
+
+            env.events().publish(
+                (symbol_short!("fac_setl"),),
+                (assignment_id, assignment.face_value - factor_share),
+            );
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("asgn"), assignment_id), &assignment);
+
+        Ok(assignment.face_value - assignment.paid_amount)
+    }
+
+    /// Write off an invoice left unpaid past its due date (factor only)
+    ///
+    /// The factor keeps whatever was paid and remains owner of the invoice
+    /// collateral, which is released so recovery can proceed off-chain.
+    ///
+    /// # Events
+    /// Emits `fac_def` with (assignment_id, paid_amount)
+    pub fn write_off(env: Env, assignment_id: u64) -> Result<(), ContractError> {
+        let mut assignment = Self::load_assignment(&env, assignment_id)?;
+
+        assignment.factor.require_auth();
+
+        if assignment.status != AssignmentStatus::Assigned
+            && assignment.status != AssignmentStatus::Acknowledged
+        {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if env.ledger().timestamp() <= assignment.due_ts {
+            return Err(ContractError::NotOverdue);
+        }
+
+        assignment.status = AssignmentStatus::Defaulted;
+        Self::registry_client(&env)?.release_assignment(&assignment.collateral_id, &assignment_id);
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("asgn"), assignment_id), &assignment);
+
+        env.events().publish(
+            (symbol_short!("fac_def"),),
+            (assignment_id, assignment.paid_amount),
+        );
+        Ok(())
+    }
+
+    /// Get contract admin
+    pub fn admin(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Get the governance address allowed to change terms
+    pub fn governance(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("gov"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Get the current (advance_rate_bps, fee_bps) terms
+    pub fn get_terms(env: Env) -> (u32, u32) {
+        let advance_rate_bps = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("adv_bps"))
+            .unwrap_or(DEFAULT_ADVANCE_RATE_BPS);
+        let fee_bps = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("fee_bps"))
+            .unwrap_or(DEFAULT_FEE_BPS);
+        (advance_rate_bps, fee_bps)
+    }
+
+    /// Get an assignment
+    pub fn get_assignment(env: Env, assignment_id: u64) -> Option<Assignment> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("asgn"), assignment_id))
+    }
+
+    /// Get the amount the debtor still owes on an assignment
+    pub fn get_outstanding(env: Env, assignment_id: u64) -> i128 {
+        Self::get_assignment(env, assignment_id)
+            .map(|assignment| assignment.face_value - assignment.paid_amount)
+            .unwrap_or(0)
+    }
+
+    fn load_assignment(env: &Env, assignment_id: u64) -> Result<Assignment, ContractError> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("asgn"), assignment_id))
+            .ok_or(ContractError::AssignmentNotFound)
+    }
+
+    fn registry_client(env: &Env) -> Result<CollateralRegistryClient<'_>, ContractError> {
+        let registry: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("coll_reg"))
+            .ok_or(ContractError::Unauthorized)?;
+        Ok(CollateralRegistryClient::new(env, &registry))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Ledger};
+
+    /// Collateral registry stand-in tracking assignment locks
+    #[contract]
+    pub struct MockCollateralRegistry;
+
+    #[contractimpl]
+    impl MockCollateralRegistry {
+        pub fn register(env: Env, id: u64, owner: Address, face_value: i128, expiry_ts: u64) {
+            let collateral = Collateral {
+                id,
+                owner,
+                face_value,
+                realized_value: face_value,
+                expiry_ts,
+                metadata_hash: BytesN::from_array(&env, &[id as u8; 32]),
+                registered_at: env.ledger().timestamp(),
+                last_valuation_ts: env.ledger().timestamp(),
+                locked: false,
+                lock_holder: None,
+                lock_ref: 0,
+            };
+            env.storage().instance().set(&id, &collateral);
+        }
+
+        pub fn get_collateral(env: Env, id: u64) -> Option<Collateral> {
+            env.storage().instance().get(&id)
+        }
+
+        pub fn assign_collateral(env: Env, id: u64, assignment_id: u64, assignee: Address) {
+            let mut collateral: Collateral = env.storage().instance().get(&id).unwrap();
+            assert!(!collateral.locked);
+            collateral.owner = assignee;
+            collateral.locked = true;
+            collateral.lock_ref = assignment_id;
+            env.storage().instance().set(&id, &collateral);
+        }
+
+        pub fn release_assignment(env: Env, id: u64, assignment_id: u64) {
+            let mut collateral: Collateral = env.storage().instance().get(&id).unwrap();
+            assert!(collateral.locked && collateral.lock_ref == assignment_id);
+            collateral.locked = false;
+            collateral.lock_ref = 0;
+            env.storage().instance().set(&id, &collateral);
+        }
+
+        pub fn settle_assignment(env: Env, id: u64, assignment_id: u64) {
+            Self::release_assignment(env.clone(), id, assignment_id);
+            env.storage()
+                .instance()
+                .set(&(symbol_short!("realized"), id), &true);
+        }
+
+        pub fn is_realized(env: Env, id: u64) -> bool {
+            env.storage()
+                .instance()
+                .get(&(symbol_short!("realized"), id))
+                .unwrap_or(false)
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: InvoiceFactoringContractClient<'a>,
+        contract_id: Address,
+        governance: Address,
+        registry: MockCollateralRegistryClient<'a>,
+        token: token::Client<'a>,
+        asset: Address,
+        seller: Address,
+        factor: Address,
+        debtor: Address,
+    }
+
+    fn setup() -> Setup<'static> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let registry_id = env.register(MockCollateralRegistry, ());
+        let registry = MockCollateralRegistryClient::new(&env, &registry_id);
+
+        let contract_id = env.register(InvoiceFactoringContract, ());
+        let client = InvoiceFactoringContractClient::new(&env, &contract_id);
+        let governance = Address::generate(&env);
+        client.initialize(&Address::generate(&env), &governance, &registry_id);
+
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let token = token::Client::new(&env, &asset);
+        let factor = Address::generate(&env);
+        let debtor = Address::generate(&env);
+        token::StellarAssetClient::new(&env, &asset).mint(&factor, &10000);
+        token::StellarAssetClient::new(&env, &asset).mint(&debtor, &10000);
+
+        let seller = Address::generate(&env);
+        registry.register(&1, &seller, &1000, &(env.ledger().timestamp() + 86400));
+
+        Setup {
+            client,
+            contract_id,
+            governance,
+            registry,
+            token,
+            asset,
+            seller,
+            factor,
+            debtor,
+            env,
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        let s = setup();
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::initialize(
+                s.env.clone(),
+                Address::generate(&s.env),
+                Address::generate(&s.env),
+                Address::generate(&s.env),
+            )
+        });
+        assert_eq!(result, Err(ContractError::AlreadyInitialized));
+        assert_eq!(
+            s.client.get_terms(),
+            (DEFAULT_ADVANCE_RATE_BPS, DEFAULT_FEE_BPS)
+        );
+        assert_eq!(s.client.governance(), s.governance);
+    }
+
+    #[test]
+    fn test_factoring_flow() {
+        let s = setup();
+        let assignment_id = s
+            .client
+            .assign_invoice(&s.seller, &s.factor, &1, &s.debtor, &s.asset);
+
+        // Seller receives 80% up front; factor now owns the locked invoice
+        assert_eq!(s.token.balance(&s.seller), 800);
+        let collateral = s.registry.get_collateral(&1).unwrap();
+        assert_eq!(collateral.owner, s.factor);
+        assert!(collateral.locked);
+        assert_eq!(collateral.lock_ref, assignment_id);
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::pay_invoice(s.env.clone(), assignment_id, 500)
+        });
+        assert_eq!(result, Err(ContractError::NotAcknowledged));
+
+        s.client.acknowledge_assignment(&assignment_id);
+
+        // First payment goes entirely to the factor
+        assert_eq!(s.client.pay_invoice(&assignment_id, &500), 500);
+        assert_eq!(s.token.balance(&s.factor), 10000 - 800 + 500);
+
+        // Overpayment is capped; factor recovers advance + 2% fee, seller gets the reserve
+        assert_eq!(s.client.pay_invoice(&assignment_id, &600), 0);
+        assert_eq!(s.token.balance(&s.debtor), 9000);
+        assert_eq!(s.token.balance(&s.factor), 10000 + 20);
+        assert_eq!(s.token.balance(&s.seller), 980);
+
+        let assignment = s.client.get_assignment(&assignment_id).unwrap();
+        assert_eq!(assignment.status, AssignmentStatus::Settled);
+        assert!(!s.registry.get_collateral(&1).unwrap().locked);
+        assert!(s.registry.is_realized(&1));
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::pay_invoice(s.env.clone(), assignment_id, 1)
+        });
+        assert_eq!(result, Err(ContractError::InvalidStatus));
+    }
+
+    #[test]
+    fn test_terms_apply_to_new_assignments() {
+        let s = setup();
+        let first = s
+            .client
+            .assign_invoice(&s.seller, &s.factor, &1, &s.debtor, &s.asset);
+
+        assert!(s.client.try_set_terms(&9000, &1500).is_err());
+        s.client.set_terms(&9000, &100);
+        assert_eq!(s.env.auths()[0].0, s.governance);
+        assert_eq!(s.client.get_terms(), (9000, 100));
+
+        s.registry
+            .register(&2, &s.seller, &2000, &(s.env.ledger().timestamp() + 86400));
+        let second = s
+            .client
+            .assign_invoice(&s.seller, &s.factor, &2, &s.debtor, &s.asset);
+
+        let first = s.client.get_assignment(&first).unwrap();
+        assert_eq!((first.advance_amount, first.fee_amount), (800, 20));
+        let second = s.client.get_assignment(&second).unwrap();
+        assert_eq!((second.advance_amount, second.fee_amount), (1800, 20));
+    }
+
+    #[test]
+    fn test_assign_requires_unencumbered_owner() {
+        let s = setup();
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::assign_invoice(
+                s.env.clone(),
+                s.debtor.clone(),
+                s.factor.clone(),
+                1,
+                s.debtor.clone(),
+                s.asset.clone(),
+            )
+        });
+        assert_eq!(result, Err(ContractError::NotCollateralOwner));
+
+        s.client
+            .assign_invoice(&s.seller, &s.factor, &1, &s.debtor, &s.asset);
+
+        // The factor now owns the locked invoice and cannot re-assign it
+        let other_factor = Address::generate(&s.env);
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::assign_invoice(
+                s.env.clone(),
+                s.factor.clone(),
+                other_factor.clone(),
+                1,
+                s.debtor.clone(),
+                s.asset.clone(),
+            )
+        });
+        assert_eq!(result, Err(ContractError::CollateralLocked));
+    }
+
+    #[test]
+    fn test_write_off_after_due_date() {
+        let s = setup();
+        let assignment_id = s
+            .client
+            .assign_invoice(&s.seller, &s.factor, &1, &s.debtor, &s.asset);
+        s.client.acknowledge_assignment(&assignment_id);
+        s.client.pay_invoice(&assignment_id, &300);
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::write_off(s.env.clone(), assignment_id)
+        });
+        assert_eq!(result, Err(ContractError::NotOverdue));
+
+        s.env.ledger().with_mut(|li| li.timestamp += 86401);
+        s.client.write_off(&assignment_id);
+
+        let assignment = s.client.get_assignment(&assignment_id).unwrap();
+        assert_eq!(assignment.status, AssignmentStatus::Defaulted);
+        assert_eq!(s.client.get_outstanding(&assignment_id), 700);
+
+        let collateral = s.registry.get_collateral(&1).unwrap();
+        assert_eq!(collateral.owner, s.factor);
+        assert!(!collateral.locked);
+        assert!(!s.registry.is_realized(&1));
+    }
+
+    #[test]
+    fn test_assign_rejects_overflowing_face_value() {
+        let s = setup();
+        s.registry.register(
+            &2,
+            &s.seller,
+            &i128::MAX,
+            &(s.env.ledger().timestamp() + 86400),
+        );
+
+        let result = s.env.as_contract(&s.contract_id, || {
+            InvoiceFactoringContract::assign_invoice(
+                s.env.clone(),
+                s.seller.clone(),
+                s.factor.clone(),
+                2,
+                s.debtor.clone(),
+                s.asset.clone(),
+            )
+        });
+        assert_eq!(result, Err(ContractError::MathOverflow));
+    }
+}
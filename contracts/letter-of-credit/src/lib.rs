@@ -0,0 +1,1180 @@
+//! Letter of Credit Contract for StelloVault
+//!
+//! This contract models documentary letters of credit: an issuing bank
+//! undertakes to pay a beneficiary once a required set of documents has been
+//! presented and found compliant. It handles issuance, document presentation,
+//! bank examination and payment at sight or on a deferred maturity date.
+
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Bytes,
+    BytesN, Env, Symbol, Vec,
+};
+
+/// Contract errors
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractError {
+    Unauthorized = 1,
+    AlreadyInitialized = 2,
+    LcNotFound = 3,
+    InvalidAmount = 4,
+    InvalidExpiry = 5,
+    InvalidDocuments = 6,
+    MissingDocument = 7,
+    InvalidStatus = 8,
+    LcExpired = 9,
+    LcNotExpired = 10,
+    NotMatured = 11,
+    ShipmentNotConfirmed = 12,
+    InvoiceAlreadyFinanced = 13,
+    ExaminationPending = 14,
+}
+
+impl From<soroban_sdk::Error> for ContractError {
+    fn from(_: soroban_sdk::Error) -> Self {
+        ContractError::Unauthorized
+    }
+}
+
+impl From<&ContractError> for soroban_sdk::Error {
+    fn from(err: &ContractError) -> Self {
+        soroban_sdk::Error::from_contract_error(*err as u32)
+    }
+}
+
+/// Documents a letter of credit can call for
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocumentType {
+    BillOfLading = 0,
+    CommercialInvoice = 1,
+    CertificateOfOrigin = 2,
+    PackingList = 3,
+    InsuranceCertificate = 4,
+    InspectionCertificate = 5,
+}
+
+/// A presented document, identified by the hash of its off-chain content
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Document {
+    pub doc_type: DocumentType,
+    pub hash: BytesN<32>,
+}
+
+/// A discrepancy the issuing bank found during examination
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Discrepancy {
+    pub doc_type: DocumentType,
+    pub reason: Symbol,
+}
+
+/// When the beneficiary is paid after the documents are accepted
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PaymentTerms {
+    /// Paid on acceptance
+    AtSight,
+    /// Paid the given number of seconds after acceptance
+    Deferred(u64),
+}
+
+/// Letter of credit lifecycle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LcStatus {
+    Issued = 0,
+    Presented = 1,
+    Refused = 2,
+    Accepted = 3,
+    Paid = 4,
+    Expired = 5,
+    Cancelled = 6,
+}
+
+/// Oracle confirmation of shipment required before the bank may accept
+///
+/// Oracles confirm against this contract's address and the LC ID encoded as
+/// 8 big-endian bytes. At least `quorum` confirmations must agree on the
+/// majority shipment payload, and its status must have reached `min_status`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShipmentCheck {
+    pub adapter: Address,
+    pub quorum: u32,
+    pub min_status: ShipmentStatus,
+}
+
+/// Letter of credit data structure
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LetterOfCredit {
+    pub id: u64,
+    pub issuing_bank: Address,
+    pub applicant: Address,
+    pub beneficiary: Address,
+    pub asset: Address,
+    pub amount: i128,
+    pub required_documents: Vec<DocumentType>,
+    pub payment_terms: PaymentTerms,
+    pub expiry_ts: u64,
+    pub status: LcStatus,
+    pub issued_at: u64,
+    pub presented_at: u64,
+    pub maturity_ts: u64, // Set on acceptance
+}
+
+/// Registry collateral (mirrors CollateralRegistry)
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Collateral {
+    pub id: u64,
+    pub owner: Address,
+    pub face_value: i128,
+    pub realized_value: i128,
+    pub expiry_ts: u64,
+    pub metadata_hash: BytesN<32>,
+    pub registered_at: u64,
+    pub last_valuation_ts: u64,
+    pub locked: bool,
+    pub lock_holder: Option<Address>,
+    pub lock_ref: u64,
+}
+
+/// Shipment milestone (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShipmentStatus {
+    Booked = 0,
+    Departed = 1,
+    InTransit = 2,
+    Arrived = 3,
+    Delivered = 4,
+}
+
+/// Shipment confirmation payload (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShipmentPayload {
+    pub carrier_code: Symbol,
+    pub tracking_hash: BytesN<32>,
+    pub port: Symbol,
+    pub status: ShipmentStatus,
+}
+
+/// Delivery confirmation payload (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryPayload {
+    pub receiver_signoff_hash: BytesN<32>,
+}
+
+/// Quality inspection payload (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QualityPayload {
+    pub grade: u32,
+    pub inspection_cert_hash: BytesN<32>,
+}
+
+/// Valuation payload (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValuationPayload {
+    pub amount: i128,
+    pub currency: Symbol,
+}
+
+/// Typed confirmation payload (mirrors OracleAdapter)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfirmationPayload {
+    Shipment(ShipmentPayload),
+    Delivery(DeliveryPayload),
+    Quality(QualityPayload),
+    Custom(Bytes),
+    Valuation(ValuationPayload),
+}
+
+/// OracleAdapter interface
+#[contractclient(name = "OracleAdapterClient")]
+pub trait OracleAdapter {
+    fn get_majority_confirmation(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        event_type: u32,
+    ) -> Option<(ConfirmationPayload, u32)>;
+}
+
+/// CollateralRegistry interface
+#[contractclient(name = "CollateralRegistryClient")]
+pub trait CollateralRegistry {
+    fn get_collateral_by_hash(env: Env, metadata_hash: BytesN<32>) -> Option<Collateral>;
+}
+
+/// Oracle event type confirming shipment (mirrors OracleAdapter)
+const EVENT_SHIPMENT: u32 = 1;
+
+/// Maximum number of documents an LC can require
+const MAX_DOCUMENTS: u32 = 6;
+
+/// Time the issuing bank has to examine a presentation (five banking days)
+const EXAMINATION_PERIOD: u64 = 5 * 86400;
+
+#[contract]
+pub struct LetterOfCreditContract;
+
+#[contractimpl]
+impl LetterOfCreditContract {
+    /// Initialize the contract with admin address
+    pub fn initialize(env: Env, admin: Address) -> Result<(), ContractError> {
+        if env.storage().instance().has(&symbol_short!("admin")) {
+            return Err(ContractError::AlreadyInitialized);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("admin"), &admin);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("lc_next"), &1u64);
+
+        env.events().publish((symbol_short!("lc_init"),), (admin,));
+        Ok(())
+    }
+
+    /// Issue a letter of credit
+    ///
+    /// The issuing bank deposits `amount` of `asset` as cover, so payment to
+    /// the beneficiary is guaranteed once compliant documents are accepted.
+    /// Both the bank and the applicant must authorize issuance.
+    ///
+    /// # Arguments
+    /// * `issuing_bank` - Bank undertaking to pay
+    /// * `applicant` - Buyer on whose behalf the LC is issued
+    /// * `beneficiary` - Seller to be paid
+    /// * `asset` - Payment asset
+    /// * `amount` - LC amount
+    /// * `required_documents` - Document set the beneficiary must present
+    /// * `payment_terms` - At sight or deferred
+    /// * `expiry_ts` - Last moment documents may be presented
+    /// * `shipment_check` - Optional oracle shipment confirmation required for acceptance
+    ///
+    /// # Events
+    /// Emits `lc_issue` with (lc_id, issuing_bank, beneficiary, amount)
+    pub fn issue_lc(
+        env: Env,
+        issuing_bank: Address,
+        applicant: Address,
+        beneficiary: Address,
+        asset: Address,
+        amount: i128,
+        required_documents: Vec<DocumentType>,
+        payment_terms: PaymentTerms,
+        expiry_ts: u64,
+        shipment_check: Option<ShipmentCheck>,
+    ) -> Result<u64, ContractError> {
+        issuing_bank.require_auth();
+        applicant.require_auth();
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        if expiry_ts <= env.ledger().timestamp() {
+            return Err(ContractError::InvalidExpiry);
+        }
+
+        if required_documents.is_empty() || required_documents.len() > MAX_DOCUMENTS {
+            return Err(ContractError::InvalidDocuments);
+        }
+        for (i, doc_type) in required_documents.iter().enumerate() {
+            if required_documents
+                .iter()
+                .skip(i + 1)
+                .any(|other| other == doc_type)
+            {
+                return Err(ContractError::InvalidDocuments);
+            }
+        }
+
+        if let Some(check) = &shipment_check {
+            if check.quorum == 0 {
+                return Err(ContractError::InvalidDocuments);
+            }
+        }
+
+        let lc_id: u64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("lc_next"))
+            .unwrap_or(1);
+
+        let token_client = token::Client::new(&env, &asset);
+        token_client.transfer(&issuing_bank, &env.current_contract_address(), &amount);
+
+        let lc = LetterOfCredit {
+            id: lc_id,
+            issuing_bank: issuing_bank.clone(),
+            applicant,
+            beneficiary: beneficiary.clone(),
+            asset,
+            amount,
+            required_documents,
+            payment_terms,
+            expiry_ts,
+            status: LcStatus::Issued,
+            issued_at: env.ledger().timestamp(),
+            presented_at: 0,
+            maturity_ts: 0,
+        };
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+        if let Some(check) = shipment_check {
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("ship_chk"), lc_id), &check);
+        }
+        env.storage()
+            .instance()
+            .set(&symbol_short!("lc_next"), &(lc_id + 1));
+
+        env.events().publish(
+            (symbol_short!("lc_issue"),),
+            (lc_id, issuing_bank, beneficiary, amount),
+        );
+
+        Ok(lc_id)
+    }
+
+    /// Present documents against a letter of credit (beneficiary only)
+    ///
+    /// Every required document type must be included. After a refusal the
+    /// beneficiary may present a corrected set until expiry. When a
+    /// collateral registry is configured, a commercial invoice that is
+    /// currently locked as collateral elsewhere is rejected.
+    ///
+    /// # Events
+    /// Emits `lc_pres` with (lc_id, document_count)
+    pub fn present_documents(
+        env: Env,
+        lc_id: u64,
+        documents: Vec<Document>,
+    ) -> Result<(), ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        lc.beneficiary.require_auth();
+
+        if lc.status != LcStatus::Issued && lc.status != LcStatus::Refused {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if env.ledger().timestamp() > lc.expiry_ts {
+            return Err(ContractError::LcExpired);
+        }
+
+        if documents.len() > MAX_DOCUMENTS {
+            return Err(ContractError::InvalidDocuments);
+        }
+
+        for doc_type in lc.required_documents.iter() {
+            if !documents.iter().any(|doc| doc.doc_type == doc_type) {
+                return Err(ContractError::MissingDocument);
+            }
+        }
+
+        if let Some(registry) = env
+            .storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("coll_reg"))
+        {
+            let registry_client = CollateralRegistryClient::new(&env, &registry);
+            for doc in documents.iter() {
+                if doc.doc_type != DocumentType::CommercialInvoice {
+                    continue;
+                }
+                if let Some(collateral) = registry_client.get_collateral_by_hash(&doc.hash) {
+                    if collateral.locked {
+                        return Err(ContractError::InvoiceAlreadyFinanced);
+                    }
+                }
+            }
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("docs"), lc_id), &documents);
+        env.storage()
+            .persistent()
+            .remove(&(symbol_short!("discrep"), lc_id));
+
+        lc.status = LcStatus::Presented;
+        lc.presented_at = env.ledger().timestamp();
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        env.events()
+            .publish((symbol_short!("lc_pres"),), (lc_id, documents.len()));
+
+        Ok(())
+    }
+
+    /// Accept a compliant presentation (issuing bank only)
+    ///
+    /// Sets the maturity date from the payment terms. At-sight LCs are paid
+    /// in the same call.
+    ///
+    /// # Returns
+    /// The maturity timestamp
+    ///
+    /// # Events
+    /// Emits `lc_acc` with (lc_id, maturity_ts)
+    pub fn accept_documents(env: Env, lc_id: u64) -> Result<u64, ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        lc.issuing_bank.require_auth();
+
+        if lc.status != LcStatus::Presented {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if let Some(check) = Self::get_shipment_check(env.clone(), lc_id) {
+            let majority = OracleAdapterClient::new(&env, &check.adapter)
+                .get_majority_confirmation(
+                    &env.current_contract_address(),
+                    &Self::lc_reference(&env, lc_id),
+                    &EVENT_SHIPMENT,
+                );
+            let confirmed = match majority {
+                Some((ConfirmationPayload::Shipment(shipment), count)) => {
+                    count >= check.quorum && shipment.status as u32 >= check.min_status as u32
+                }
+                _ => false,
+            };
+            if !confirmed {
+                return Err(ContractError::ShipmentNotConfirmed);
+            }
+        }
+
+        Self::accept(&env, &mut lc, env.ledger().timestamp());
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        Ok(lc.maturity_ts)
+    }
+
+    /// Refuse a presentation with discrepancies (issuing bank only)
+    ///
+    /// The beneficiary may correct the documents and present again until
+    /// the LC expires.
+    ///
+    /// # Events
+    /// Emits `lc_ref` with (lc_id, discrepancy_count)
+    pub fn refuse_documents(
+        env: Env,
+        lc_id: u64,
+        discrepancies: Vec<Discrepancy>,
+    ) -> Result<(), ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        lc.issuing_bank.require_auth();
+
+        if lc.status != LcStatus::Presented {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if discrepancies.is_empty() || discrepancies.len() > MAX_DOCUMENTS {
+            return Err(ContractError::InvalidDocuments);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("discrep"), lc_id), &discrepancies);
+
+        lc.status = LcStatus::Refused;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        env.events()
+            .publish((symbol_short!("lc_ref"),), (lc_id, discrepancies.len()));
+
+        Ok(())
+    }
+
+    /// Pay the beneficiary of an accepted LC once it has matured
+    ///
+    /// Callable by anyone.
+    ///
+    /// # Events
+    /// Emits `lc_paid` with (lc_id, beneficiary, amount)
+    pub fn pay(env: Env, lc_id: u64) -> Result<(), ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        if lc.status != LcStatus::Accepted {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        if env.ledger().timestamp() < lc.maturity_ts {
+            return Err(ContractError::NotMatured);
+        }
+
+        Self::settle(&env, &mut lc);
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        Ok(())
+    }
+
+    /// Expire an LC whose presentation period ended without acceptance
+    ///
+    /// Returns the cover to the issuing bank. An LC with documents still
+    /// under examination cannot expire until the examination period after
+    /// presentation has also run out. A bank that lets that period pass
+    /// without accepting or refusing is precluded from claiming the
+    /// documents are non-compliant (UCP 600 art. 16(f)), so the
+    /// presentation is accepted as of the end of the period instead of the
+    /// cover being returned.
+    ///
+    /// # Events
+    /// Emits `lc_exp` with (lc_id,), or `lc_acc` for an unexamined presentation
+    pub fn expire_lc(env: Env, lc_id: u64) -> Result<(), ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        let now = env.ledger().timestamp();
+        if now <= lc.expiry_ts {
+            return Err(ContractError::LcNotExpired);
+        }
+
+        match lc.status {
+            LcStatus::Issued | LcStatus::Refused => {}
+            LcStatus::Presented => {
+                let examination_end = lc.presented_at.saturating_add(EXAMINATION_PERIOD);
+                if now <= examination_end {
+                    return Err(ContractError::ExaminationPending);
+                }
+
+                Self::accept(&env, &mut lc, examination_end);
+                env.storage()
+                    .persistent()
+                    .set(&(symbol_short!("lc"), lc_id), &lc);
+                return Ok(());
+            }
+            _ => return Err(ContractError::InvalidStatus),
+        }
+
+        Self::refund_bank(&env, &lc);
+
+        lc.status = LcStatus::Expired;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        env.events().publish((symbol_short!("lc_exp"),), (lc_id,));
+        Ok(())
+    }
+
+    /// Cancel an LC before any documents are presented
+    ///
+    /// LCs are irrevocable, so the bank, applicant and beneficiary must all
+    /// agree.
+    ///
+    /// # Events
+    /// Emits `lc_cncl` with (lc_id,)
+    pub fn cancel_lc(env: Env, lc_id: u64) -> Result<(), ContractError> {
+        let mut lc = Self::load_lc(&env, lc_id)?;
+
+        lc.issuing_bank.require_auth();
+        lc.applicant.require_auth();
+        lc.beneficiary.require_auth();
+
+        if lc.status != LcStatus::Issued {
+            return Err(ContractError::InvalidStatus);
+        }
+
+        Self::refund_bank(&env, &lc);
+
+        lc.status = LcStatus::Cancelled;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("lc"), lc_id), &lc);
+
+        env.events().publish((symbol_short!("lc_cncl"),), (lc_id,));
+        Ok(())
+    }
+
+    /// Set the collateral registry used to detect invoices already financed (admin only)
+    pub fn set_collateral_registry(env: Env, registry: Address) -> Result<(), ContractError> {
+        let admin = Self::admin(env.clone())?;
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("coll_reg"), &registry);
+        Ok(())
+    }
+
+    /// Get contract admin
+    pub fn admin(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("admin"))
+            .ok_or(ContractError::Unauthorized)
+    }
+
+    /// Get a letter of credit
+    pub fn get_lc(env: Env, lc_id: u64) -> Option<LetterOfCredit> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("lc"), lc_id))
+    }
+
+    /// Get the oracle shipment check an LC requires, if any
+    pub fn get_shipment_check(env: Env, lc_id: u64) -> Option<ShipmentCheck> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("ship_chk"), lc_id))
+    }
+
+    /// Get the documents of the latest presentation
+    pub fn get_documents(env: Env, lc_id: u64) -> Vec<Document> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("docs"), lc_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the discrepancies of the latest refusal
+    pub fn get_discrepancies(env: Env, lc_id: u64) -> Vec<Discrepancy> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("discrep"), lc_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    fn load_lc(env: &Env, lc_id: u64) -> Result<LetterOfCredit, ContractError> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("lc"), lc_id))
+            .ok_or(ContractError::LcNotFound)
+    }
+
+    /// Accept the presentation as of `accepted_at`, paying at once if the
+    /// LC is at sight
+    fn accept(env: &Env, lc: &mut LetterOfCredit, accepted_at: u64) {
+        lc.maturity_ts = match lc.payment_terms {
+            PaymentTerms::AtSight => accepted_at,
+            PaymentTerms::Deferred(period) => accepted_at.saturating_add(period),
+        };
+        lc.status = LcStatus::Accepted;
+
+        env.events()
+            .publish((symbol_short!("lc_acc"),), (lc.id, lc.maturity_ts));
+
+        if lc.payment_terms == PaymentTerms::AtSight {
+            Self::settle(env, lc);
+        }
+    }
+
+    /// Pay the beneficiary out of the bank's cover
+    fn settle(env: &Env, lc: &mut LetterOfCredit) {
+        let token_client = token::Client::new(env, &lc.asset);
+        token_client.transfer(&env.current_contract_address(), &lc.beneficiary, &lc.amount);
+
+        lc.status = LcStatus::Paid;
+
+        env.events().publish(
+            (symbol_short!("lc_paid"),),
+            (lc.id, lc.beneficiary.clone(), lc.amount),
+        );
+    }
+
+    fn refund_bank(env: &Env, lc: &LetterOfCredit) {
+        let token_client = token::Client::new(env, &lc.asset);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &lc.issuing_bank,
+            &lc.amount,
+        );
+    }
+
+    // LC ID as confirmed by oracles on the OracleAdapter
+    fn lc_reference(env: &Env, lc_id: u64) -> Bytes {
+        Bytes::from_slice(env, &lc_id.to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec,
+    };
+
+    /// Collateral registry stand-in holding one locked document hash
+    #[contract]
+    pub struct MockCollateralRegistry;
+
+    #[contractimpl]
+    impl MockCollateralRegistry {
+        pub fn set_locked(env: Env, metadata_hash: BytesN<32>, owner: Address) {
+            let collateral = Collateral {
+                id: 1,
+                owner,
+                face_value: 1000,
+                realized_value: 1000,
+                expiry_ts: env.ledger().timestamp() + 86400,
+                metadata_hash: metadata_hash.clone(),
+                registered_at: env.ledger().timestamp(),
+                last_valuation_ts: env.ledger().timestamp(),
+                locked: true,
+                lock_holder: Some(env.current_contract_address()),
+                lock_ref: 1,
+            };
+            env.storage().instance().set(&metadata_hash, &collateral);
+        }
+
+        pub fn get_collateral_by_hash(env: Env, metadata_hash: BytesN<32>) -> Option<Collateral> {
+            env.storage().instance().get(&metadata_hash)
+        }
+    }
+
+    struct Setup<'a> {
+        env: Env,
+        client: LetterOfCreditContractClient<'a>,
+        contract_id: Address,
+        token: token::Client<'a>,
+        asset: Address,
+        bank: Address,
+        applicant: Address,
+        beneficiary: Address,
+    }
+
+    fn setup() -> Setup<'static> {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(LetterOfCreditContract, ());
+        let client = LetterOfCreditContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let token = token::Client::new(&env, &asset);
+        let bank = Address::generate(&env);
+        token::StellarAssetClient::new(&env, &asset).mint(&bank, &10000);
+
+        Setup {
+            client,
+            contract_id,
+            token,
+            asset,
+            bank,
+            applicant: Address::generate(&env),
+            beneficiary: Address::generate(&env),
+            env,
+        }
+    }
+
+    fn document_set(env: &Env) -> Vec<Document> {
+        vec![
+            env,
+            Document {
+                doc_type: DocumentType::BillOfLading,
+                hash: BytesN::from_array(env, &[1u8; 32]),
+            },
+            Document {
+                doc_type: DocumentType::CommercialInvoice,
+                hash: BytesN::from_array(env, &[2u8; 32]),
+            },
+            Document {
+                doc_type: DocumentType::CertificateOfOrigin,
+                hash: BytesN::from_array(env, &[3u8; 32]),
+            },
+        ]
+    }
+
+    fn required_set(env: &Env) -> Vec<DocumentType> {
+        vec![
+            env,
+            DocumentType::BillOfLading,
+            DocumentType::CommercialInvoice,
+            DocumentType::CertificateOfOrigin,
+        ]
+    }
+
+    #[test]
+    fn test_initialize() {
+        let s = setup();
+        let result = s.env.as_contract(&s.contract_id, || {
+            LetterOfCreditContract::initialize(s.env.clone(), Address::generate(&s.env))
+        });
+        assert_eq!(result, Err(ContractError::AlreadyInitialized));
+    }
+
+    #[test]
+    fn test_issue_present_and_pay_at_sight() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        // Duplicate document types are rejected
+        let duplicated = vec![env, DocumentType::BillOfLading, DocumentType::BillOfLading];
+        assert!(s
+            .client
+            .try_issue_lc(
+                &s.bank,
+                &s.applicant,
+                &s.beneficiary,
+                &s.asset,
+                &5000,
+                &duplicated,
+                &PaymentTerms::AtSight,
+                &expiry,
+                &None
+            )
+            .is_err());
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &None,
+        );
+        assert_eq!(s.token.balance(&s.contract_id), 5000);
+        assert_eq!(s.client.get_lc(&lc_id).unwrap().status, LcStatus::Issued);
+
+        // The certificate of origin is missing
+        let mut incomplete = document_set(env);
+        incomplete.pop_back();
+        env.as_contract(&s.contract_id, || {
+            let result =
+                LetterOfCreditContract::present_documents(env.clone(), lc_id, incomplete.clone());
+            assert_eq!(result, Err(ContractError::MissingDocument));
+        });
+
+        s.client.present_documents(&lc_id, &document_set(env));
+        assert_eq!(s.client.get_documents(&lc_id), document_set(env));
+
+        let maturity = s.client.accept_documents(&lc_id);
+        assert_eq!(maturity, env.ledger().timestamp());
+
+        let lc = s.client.get_lc(&lc_id).unwrap();
+        assert_eq!(lc.status, LcStatus::Paid);
+        assert_eq!(s.token.balance(&s.beneficiary), 5000);
+        assert_eq!(s.token.balance(&s.contract_id), 0);
+    }
+
+    #[test]
+    fn test_refusal_and_deferred_payment() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::Deferred(86400),
+            &expiry,
+            &None,
+        );
+
+        s.client.present_documents(&lc_id, &document_set(env));
+
+        let discrepancies = vec![
+            env,
+            Discrepancy {
+                doc_type: DocumentType::CommercialInvoice,
+                reason: symbol_short!("amount"),
+            },
+        ];
+        s.client.refuse_documents(&lc_id, &discrepancies);
+        assert_eq!(s.client.get_lc(&lc_id).unwrap().status, LcStatus::Refused);
+        assert_eq!(s.client.get_discrepancies(&lc_id), discrepancies);
+
+        // A corrected presentation clears the discrepancies
+        s.client.present_documents(&lc_id, &document_set(env));
+        assert_eq!(s.client.get_discrepancies(&lc_id).len(), 0);
+
+        let maturity = s.client.accept_documents(&lc_id);
+        assert_eq!(maturity, env.ledger().timestamp() + 86400);
+        assert_eq!(s.client.get_lc(&lc_id).unwrap().status, LcStatus::Accepted);
+
+        env.as_contract(&s.contract_id, || {
+            let result = LetterOfCreditContract::pay(env.clone(), lc_id);
+            assert_eq!(result, Err(ContractError::NotMatured));
+        });
+
+        // The accepted LC survives its presentation expiry and pays at maturity
+        env.ledger().set_timestamp(maturity);
+        assert!(s.client.try_expire_lc(&lc_id).is_err());
+        s.client.pay(&lc_id);
+        assert_eq!(s.client.get_lc(&lc_id).unwrap().status, LcStatus::Paid);
+        assert_eq!(s.token.balance(&s.beneficiary), 5000);
+    }
+
+    fn shipment(
+        env: &Env,
+        status: oracle_adapter::ShipmentStatus,
+    ) -> oracle_adapter::ConfirmationPayload {
+        oracle_adapter::ConfirmationPayload::Shipment(oracle_adapter::ShipmentPayload {
+            carrier_code: Symbol::new(env, "MAEU"),
+            tracking_hash: BytesN::from_array(env, &[9u8; 32]),
+            port: Symbol::new(env, "NGLOS"),
+            status,
+        })
+    }
+
+    #[test]
+    fn test_acceptance_requires_shipment_confirmation() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let adapter_id = env.register(oracle_adapter::OracleAdapter, ());
+        let adapter = oracle_adapter::OracleAdapterClient::new(env, &adapter_id);
+        adapter.initialize(&Address::generate(env));
+        let oracles = [
+            Address::generate(env),
+            Address::generate(env),
+            Address::generate(env),
+        ];
+        for oracle in oracles.iter() {
+            adapter.add_oracle(oracle, &BytesN::from_array(env, &[7u8; 32]), &Vec::new(env));
+        }
+        let check = ShipmentCheck {
+            adapter: adapter_id.clone(),
+            quorum: 2,
+            min_status: ShipmentStatus::Departed,
+        };
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &Some(check),
+        );
+        s.client.present_documents(&lc_id, &document_set(env));
+
+        let reference = Bytes::from_slice(env, &lc_id.to_be_bytes());
+        let signature = Bytes::from_slice(env, b"signature");
+        let confirm = |oracle: &Address, contract: &Address, sequence: u64, status| {
+            adapter.confirm_event(
+                oracle,
+                contract,
+                &reference,
+                &EVENT_SHIPMENT,
+                &sequence,
+                &shipment(env, status),
+                &signature,
+            );
+        };
+        let departed = oracle_adapter::ShipmentStatus::Departed;
+        let booked = oracle_adapter::ShipmentStatus::Booked;
+
+        // Confirmations against another contract's reference do not count
+        let other = Address::generate(env);
+        confirm(&oracles[0], &other, 1, departed);
+        confirm(&oracles[1], &other, 1, departed);
+        confirm(&oracles[0], &s.contract_id, 1, departed);
+        assert!(s.client.try_accept_documents(&lc_id).is_err());
+
+        // Two confirmations that disagree do not make a quorum
+        confirm(&oracles[1], &s.contract_id, 1, booked);
+        assert!(s.client.try_accept_documents(&lc_id).is_err());
+
+        // An agreeing quorum that has not reached the required status
+        confirm(&oracles[2], &s.contract_id, 1, booked);
+        assert!(s.client.try_accept_documents(&lc_id).is_err());
+
+        confirm(&oracles[1], &s.contract_id, 2, departed);
+        assert!(s.client.try_accept_documents(&lc_id).is_ok());
+        assert_eq!(s.client.get_lc(&lc_id).unwrap().status, LcStatus::Paid);
+    }
+
+    #[test]
+    fn test_financed_invoice_is_rejected() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let registry_id = env.register(MockCollateralRegistry, ());
+        MockCollateralRegistryClient::new(env, &registry_id)
+            .set_locked(&BytesN::from_array(env, &[2u8; 32]), &s.beneficiary);
+        s.client.set_collateral_registry(&registry_id);
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &None,
+        );
+
+        env.as_contract(&s.contract_id, || {
+            let result =
+                LetterOfCreditContract::present_documents(env.clone(), lc_id, document_set(env));
+            assert_eq!(result, Err(ContractError::InvoiceAlreadyFinanced));
+        });
+    }
+
+    #[test]
+    fn test_expire_and_cancel() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let cancelled = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &3000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &None,
+        );
+        let expiring = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &4000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &None,
+        );
+        assert_eq!(s.token.balance(&s.bank), 3000);
+
+        s.client.cancel_lc(&cancelled);
+        assert_eq!(
+            s.client.get_lc(&cancelled).unwrap().status,
+            LcStatus::Cancelled
+        );
+        assert_eq!(s.token.balance(&s.bank), 6000);
+
+        env.as_contract(&s.contract_id, || {
+            let result = LetterOfCreditContract::expire_lc(env.clone(), expiring);
+            assert_eq!(result, Err(ContractError::LcNotExpired));
+        });
+
+        // Late presentation is refused and the cover returns to the bank
+        env.ledger().set_timestamp(expiry + 1);
+        env.as_contract(&s.contract_id, || {
+            let result =
+                LetterOfCreditContract::present_documents(env.clone(), expiring, document_set(env));
+            assert_eq!(result, Err(ContractError::LcExpired));
+        });
+
+        s.client.expire_lc(&expiring);
+        assert_eq!(
+            s.client.get_lc(&expiring).unwrap().status,
+            LcStatus::Expired
+        );
+        assert_eq!(s.token.balance(&s.bank), 10000);
+    }
+
+    #[test]
+    fn test_unexamined_presentation_expires() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::AtSight,
+            &expiry,
+            &None,
+        );
+
+        env.ledger().set_timestamp(expiry);
+        s.client.present_documents(&lc_id, &document_set(env));
+
+        // The bank still has its examination period after the LC expires
+        env.ledger().set_timestamp(expiry + EXAMINATION_PERIOD);
+        env.as_contract(&s.contract_id, || {
+            let result = LetterOfCreditContract::expire_lc(env.clone(), lc_id);
+            assert_eq!(result, Err(ContractError::ExaminationPending));
+        });
+
+        // A bank that never examines the documents must honour them
+        env.ledger().set_timestamp(expiry + EXAMINATION_PERIOD + 1);
+        s.client.expire_lc(&lc_id);
+        let lc = s.client.get_lc(&lc_id).unwrap();
+        assert_eq!(lc.status, LcStatus::Paid);
+        assert_eq!(lc.maturity_ts, expiry + EXAMINATION_PERIOD);
+        assert_eq!(s.token.balance(&s.bank), 5000);
+        assert_eq!(s.token.balance(&s.beneficiary), 5000);
+    }
+
+    #[test]
+    fn test_unexamined_deferred_presentation_matures() {
+        let s = setup();
+        let env = &s.env;
+        let expiry = env.ledger().timestamp() + 1000;
+
+        let lc_id = s.client.issue_lc(
+            &s.bank,
+            &s.applicant,
+            &s.beneficiary,
+            &s.asset,
+            &5000,
+            &required_set(env),
+            &PaymentTerms::Deferred(86400),
+            &expiry,
+            &None,
+        );
+        s.client.present_documents(&lc_id, &document_set(env));
+
+        // Deferred payment runs from the end of the examination period
+        let examination_end = env.ledger().timestamp() + EXAMINATION_PERIOD;
+        env.ledger().set_timestamp(examination_end + 1);
+        s.client.expire_lc(&lc_id);
+        let lc = s.client.get_lc(&lc_id).unwrap();
+        assert_eq!(lc.status, LcStatus::Accepted);
+        assert_eq!(lc.maturity_ts, examination_end + 86400);
+        assert_eq!(s.token.balance(&s.contract_id), 5000);
+
+        env.ledger().set_timestamp(lc.maturity_ts);
+        s.client.pay(&lc_id);
+        assert_eq!(s.token.balance(&s.beneficiary), 5000);
+    }
+}
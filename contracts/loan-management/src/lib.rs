@@ -5,7 +5,9 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, Address, Env};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, token, Address, Env,
+};
 
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -30,6 +32,7 @@ pub enum ContractError {
     InvalidRateParameters = 9,
     RiskEngineNotSet = 10,
     MathOverflow = 11,
+    InvalidFeeConfig = 12,
 }
 
 impl From<soroban_sdk::Error> for ContractError {
@@ -58,8 +61,8 @@ pub struct RateParameters {
     pub max_rate: u32,
 }
 
-impl RateParameters {
-    pub fn default() -> Self {
+impl Default for RateParameters {
+    fn default() -> Self {
         Self {
             base_rate: 200,      // 2%
             risk_premium: 100,   // 1% per risk unit
@@ -69,6 +72,19 @@ impl RateParameters {
     }
 }
 
+/// Protocol fee configuration (mirrors StelloVault)
+///
+/// Only `origination_bps` applies here. The borrower pays the origination fee
+/// to the treasury, in the loan's asset, when the loan is issued.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    pub origination_bps: u32,
+    pub escrow_release_bps: u32,
+    pub liquidation_share_bps: u32,
+    pub treasury: Address,
+}
+
 /// Risk score from RiskAssessment contract
 #[contracttype]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -86,12 +102,20 @@ pub struct Loan {
     pub escrow_id: u64,
     pub borrower: Address,
     pub lender: Address,
+    pub asset: Address, // Loan asset, also the asset fees are charged in
     pub amount: i128,
     pub interest_rate: u32, // Basis points (e.g., 500 = 5%)
     pub deadline: u64,
     pub status: LoanStatus,
 }
 
+/// RiskAssessment position registry interface
+#[contractclient(name = "RiskEngineClient")]
+pub trait RiskEngine {
+    fn track_position(env: Env, position_id: u64);
+    fn untrack_position(env: Env, position_id: u64);
+}
+
 #[contract]
 pub struct LoanManagement;
 
@@ -145,7 +169,7 @@ impl LoanManagement {
             .storage()
             .instance()
             .get(&symbol_short!("rate_prm"))
-            .unwrap_or(RateParameters::default());
+            .unwrap_or_default();
 
         // Get risk score from RiskAssessment contract
         let risk_factor = Self::get_borrower_risk_factor(&env, &borrower)?;
@@ -207,6 +231,16 @@ impl LoanManagement {
         Ok(1)
     }
 
+    /// Drop a closed position from the risk engine's active registry
+    ///
+    /// Liquidated loans are removed by the risk engine itself, which is the
+    /// caller of `mark_liquidated`.
+    fn untrack_position(env: &Env, escrow_id: u64) {
+        if let Some(risk_engine) = Self::get_risk_engine(env.clone()) {
+            RiskEngineClient::new(env, &risk_engine).untrack_position(&escrow_id);
+        }
+    }
+
     /// Calculate protocol utilization ratio in basis points
     ///
     /// Utilization = (total_borrowed / total_liquidity) * 10000
@@ -275,7 +309,7 @@ impl LoanManagement {
         env.storage()
             .instance()
             .get(&symbol_short!("rate_prm"))
-            .unwrap_or(RateParameters::default())
+            .unwrap_or_default()
     }
 
     /// Update rate parameters (governance only)
@@ -348,10 +382,14 @@ impl LoanManagement {
 
     /// Issue a new loan backed by an escrow with dynamic interest rate
     ///
+    /// When an origination fee is configured the borrower must also
+    /// authorize, and pays the fee to the treasury in the loan's asset.
+    ///
     /// # Arguments
     /// * `escrow_id` - The unique identifier of the escrowed collateral
     /// * `borrower` - Address of the borrower
     /// * `lender` - Address of the lender
+    /// * `asset` - Asset the loan is denominated in
     /// * `amount` - Loan amount
     /// * `duration` - Duration in seconds
     ///
@@ -362,6 +400,7 @@ impl LoanManagement {
         escrow_id: u64,
         borrower: Address,
         lender: Address,
+        asset: Address,
         amount: i128,
         duration: u64,
     ) -> Result<(u64, u32), ContractError> {
@@ -376,6 +415,16 @@ impl LoanManagement {
         // Calculate dynamic interest rate
         let interest_rate = Self::get_dynamic_rate(env.clone(), borrower.clone(), amount)?;
 
+        let fee_config = Self::get_fee_config(env.clone());
+        let origination_bps = fee_config
+            .as_ref()
+            .map(|config| config.origination_bps)
+            .unwrap_or(0);
+        let origination_fee = amount
+            .checked_mul(origination_bps as i128)
+            .ok_or(ContractError::MathOverflow)?
+            / 10000;
+
         let loan_id: u64 = env
             .storage()
             .instance()
@@ -392,6 +441,7 @@ impl LoanManagement {
             escrow_id,
             borrower: borrower.clone(),
             lender: lender.clone(),
+            asset: asset.clone(),
             amount,
             interest_rate,
             deadline,
@@ -402,6 +452,31 @@ impl LoanManagement {
         env.storage().persistent().set(&loan_id, &loan);
         // Map escrow to loan ID to prevent duplicates
         env.storage().persistent().set(&escrow_key, &loan_id);
+        if let (true, Some(config)) = (origination_fee > 0, fee_config) {
+            if borrower != lender {
+                borrower.require_auth();
+            }
+
+            token::Client::new(&env, &asset).transfer(
+                &borrower,
+                &config.treasury,
+                &origination_fee,
+            );
+
+            env.storage()
+                .persistent()
+                .set(&(symbol_short!("orig_fee"), loan_id), &origination_fee);
+            let fees_key = (symbol_short!("fees"), asset.clone());
+            let accrued = Self::get_accrued_fees(env.clone(), asset.clone());
+            env.storage()
+                .persistent()
+                .set(&fees_key, &(accrued + origination_fee));
+
+            env.events().publish(
+                (symbol_short!("fee_acc"),),
+                (loan_id, asset, origination_fee),
+            );
+        }
 
         env.storage()
             .instance()
@@ -417,6 +492,11 @@ impl LoanManagement {
             .instance()
             .set(&symbol_short!("tot_bor"), &(total_borrowed + amount));
 
+        // Register the position with the risk engine for health scanning
+        if let Some(risk_engine) = Self::get_risk_engine(env.clone()) {
+            RiskEngineClient::new(&env, &risk_engine).track_position(&escrow_id);
+        }
+
         // Emit LoanIssued event with dynamic rate
         env.events().publish(
             (symbol_short!("loan_iss"),),
@@ -457,7 +537,9 @@ impl LoanManagement {
         // For simplicity, we assume interest is fixed and "amount" passed is total
         // In a real scenario, we'd calculate interest: amount * (1 + rate/10000)
         let interest = (loan.amount * (loan.interest_rate as i128)) / 10000;
-        let total_due = loan.amount + interest;
+        // Debt already covered by partial liquidations is no longer owed
+        let liquidated = Self::get_liquidation_paid(env.clone(), loan_id);
+        let total_due = (loan.amount + interest - liquidated).max(0);
 
         if amount < total_due {
             return Err(ContractError::InsufficientAmount);
@@ -477,6 +559,8 @@ impl LoanManagement {
             .instance()
             .set(&symbol_short!("tot_bor"), &new_borrowed);
 
+        Self::untrack_position(&env, loan.escrow_id);
+
         // Emit LoanRepaid event
         env.events()
             .publish((symbol_short!("loan_rep"),), (loan_id, amount));
@@ -504,6 +588,8 @@ impl LoanManagement {
         loan.status = LoanStatus::Defaulted;
         env.storage().persistent().set(&loan_id, &loan);
 
+        Self::untrack_position(&env, loan.escrow_id);
+
         // Emit LoanDefaulted event
         env.events()
             .publish((symbol_short!("loan_def"),), (loan_id,));
@@ -557,6 +643,58 @@ impl LoanManagement {
         Ok(())
     }
 
+    /// Record debt repaid by a partial liquidation
+    ///
+    /// # Arguments
+    /// * `loan_id` - The loan whose debt was paid down
+    /// * `amount` - Debt covered by the liquidator
+    ///
+    /// # Authorization
+    /// Only callable by the registered risk engine contract
+    pub fn record_liquidation_payment(
+        env: Env,
+        loan_id: u64,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        let risk_engine: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("risk_eng"))
+            .ok_or(ContractError::Unauthorized)?;
+
+        risk_engine.require_auth();
+
+        let loan: Loan = env
+            .storage()
+            .persistent()
+            .get(&loan_id)
+            .ok_or(ContractError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(ContractError::LoanNotActive);
+        }
+
+        let paid = Self::get_liquidation_paid(env.clone(), loan_id)
+            .checked_add(amount)
+            .ok_or(ContractError::MathOverflow)?;
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("liq_paid"), loan_id), &paid);
+
+        env.events()
+            .publish((symbol_short!("loan_lpay"),), (loan_id, amount));
+
+        Ok(())
+    }
+
+    /// Get the debt covered by liquidations on a loan
+    pub fn get_liquidation_paid(env: Env, loan_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("liq_paid"), loan_id))
+            .unwrap_or(0)
+    }
+
     /// Set the risk engine contract address
     ///
     /// # Arguments
@@ -589,6 +727,87 @@ impl LoanManagement {
         env.storage().instance().get(&symbol_short!("risk_eng"))
     }
 
+    /// Set the governance address allowed to change protocol fees
+    ///
+    /// # Arguments
+    /// * `governance` - Address of the governance contract
+    ///
+    /// # Authorization
+    /// Callable by admin until governance is set, then only by governance
+    pub fn set_governance(env: Env, governance: Address) -> Result<(), ContractError> {
+        let current = match Self::get_governance(env.clone()) {
+            Some(current) => current,
+            None => env
+                .storage()
+                .instance()
+                .get(&symbol_short!("admin"))
+                .ok_or(ContractError::Unauthorized)?,
+        };
+
+        current.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("gov"), &governance);
+
+        // Emit GovernanceSet event
+        env.events()
+            .publish((symbol_short!("gov_set"),), (governance,));
+
+        Ok(())
+    }
+
+    /// Get the governance address
+    pub fn get_governance(env: Env) -> Option<Address> {
+        env.storage().instance().get(&symbol_short!("gov"))
+    }
+
+    /// Update the protocol fee configuration (governance only)
+    ///
+    /// New fees apply to loans issued afterwards. Only `origination_bps` is
+    /// validated here; the other fields belong to the vault and risk engine.
+    pub fn update_fee_config(env: Env, config: FeeConfig) -> Result<(), ContractError> {
+        let governance = Self::get_governance(env.clone()).ok_or(ContractError::Unauthorized)?;
+
+        governance.require_auth();
+
+        if config.origination_bps > 10000 {
+            return Err(ContractError::InvalidFeeConfig);
+        }
+
+        env.storage()
+            .instance()
+            .set(&symbol_short!("fee_cfg"), &config);
+
+        env.events().publish(
+            (symbol_short!("fee_upd"),),
+            (config.origination_bps, config.treasury),
+        );
+
+        Ok(())
+    }
+
+    /// Get the protocol fee configuration, if fees are enabled
+    pub fn get_fee_config(env: Env) -> Option<FeeConfig> {
+        env.storage().instance().get(&symbol_short!("fee_cfg"))
+    }
+
+    /// Get the origination fee charged on a loan
+    pub fn get_origination_fee(env: Env, loan_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("orig_fee"), loan_id))
+            .unwrap_or(0)
+    }
+
+    /// Get cumulative origination fees paid to the treasury in an asset
+    pub fn get_accrued_fees(env: Env, asset: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("fees"), asset))
+            .unwrap_or(0)
+    }
+
     /// Get loan details
     pub fn get_loan(env: Env, loan_id: u64) -> Option<Loan> {
         env.storage().persistent().get(&loan_id)
@@ -605,7 +824,25 @@ impl LoanManagement {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, testutils::Ledger as _, Env};
+    use soroban_sdk::{contract, testutils::Address as _, testutils::Ledger as _, Env};
+
+    #[contract]
+    pub struct MockRiskEngine;
+
+    #[contractimpl]
+    impl MockRiskEngine {
+        pub fn track_position(env: Env, position_id: u64) {
+            env.storage().persistent().set(&position_id, &true);
+        }
+
+        pub fn untrack_position(env: Env, position_id: u64) {
+            env.storage().persistent().remove(&position_id);
+        }
+
+        pub fn is_tracked(env: Env, position_id: u64) -> bool {
+            env.storage().persistent().has(&position_id)
+        }
+    }
 
     #[test]
     fn test_initialize() {
@@ -634,6 +871,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -648,7 +886,7 @@ mod test {
         let duration = 3600u64; // 1 hour
 
         let (loan_id, interest_rate) =
-            client.issue_loan(&escrow_id, &borrower, &lender, &amount, &duration);
+            client.issue_loan(&escrow_id, &borrower, &lender, &asset, &amount, &duration);
         assert_eq!(loan_id, 1);
         assert!(interest_rate > 0); // Dynamic rate should be calculated
 
@@ -669,6 +907,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -677,10 +916,10 @@ mod test {
         client.update_total_liquidity(&10000);
 
         let escrow_id = 1u64;
-        client.issue_loan(&escrow_id, &borrower, &lender, &1000, &3600);
+        client.issue_loan(&escrow_id, &borrower, &lender, &asset, &1000, &3600);
 
         // Should fail
-        client.issue_loan(&escrow_id, &borrower, &lender, &1000, &3600);
+        client.issue_loan(&escrow_id, &borrower, &lender, &asset, &1000, &3600);
     }
 
     #[test]
@@ -691,6 +930,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -698,7 +938,8 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, interest_rate) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         // Calculate total due based on dynamic rate
         let interest = (1000 * interest_rate as i128) / 10000;
@@ -710,6 +951,75 @@ mod test {
         assert_eq!(loan.status, LoanStatus::Repaid);
     }
 
+    #[test]
+    fn test_origination_fee_charged_at_issuance() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let governance = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let treasury = Address::generate(&env);
+
+        let contract_id = env.register(LoanManagement, ());
+        let client = LoanManagementClient::new(&env, &contract_id);
+
+        client.initialize(&admin);
+        client.update_total_liquidity(&10000);
+
+        let invalid = FeeConfig {
+            origination_bps: 10001,
+            escrow_release_bps: 0,
+            liquidation_share_bps: 0,
+            treasury: treasury.clone(),
+        };
+        let config = FeeConfig {
+            origination_bps: 100,
+            ..invalid.clone()
+        };
+
+        // Fees are governed; without a governance address nobody can set them
+        assert!(client.try_update_fee_config(&config).is_err());
+        client.set_governance(&governance);
+        assert_eq!(client.get_governance(), Some(governance.clone()));
+
+        assert!(client.try_update_fee_config(&invalid).is_err());
+        client.update_fee_config(&config);
+        assert_eq!(env.auths()[0].0, governance);
+        assert_eq!(client.get_fee_config(), Some(config));
+
+        let asset = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let token = token::Client::new(&env, &asset);
+        token::StellarAssetClient::new(&env, &asset).mint(&borrower, &100);
+
+        // A borrower who cannot pay the fee gets no loan
+        let unfunded = Address::generate(&env);
+        assert!(client
+            .try_issue_loan(&1, &unfunded, &lender, &asset, &1000, &3600)
+            .is_err());
+
+        // The 1% origination fee is paid by the borrower, in the loan's asset, on issuance
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
+        assert!(env.auths().iter().any(|(address, _)| *address == borrower));
+        assert_eq!(client.get_origination_fee(&loan_id), 10);
+        assert_eq!(client.get_accrued_fees(&asset), 10);
+        assert_eq!(token.balance(&treasury), 10);
+        assert_eq!(token.balance(&borrower), 90);
+
+        // Repayment then owes principal and interest only
+        let interest = (1000 * interest_rate as i128) / 10000;
+        client.repay_loan(&loan_id, &(1000 + interest));
+        assert_eq!(
+            client.get_loan(&loan_id).unwrap().status,
+            LoanStatus::Repaid
+        );
+        assert_eq!(client.get_accrued_fees(&asset), 10);
+    }
+
     #[test]
     fn test_mark_default_success() {
         let env = Env::default();
@@ -718,6 +1028,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -726,7 +1037,7 @@ mod test {
         client.update_total_liquidity(&10000);
 
         let duration = 3600u64;
-        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &duration);
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &asset, &1000, &duration);
 
         // Advance ledger time
         env.ledger().with_mut(|li| {
@@ -748,6 +1059,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -755,7 +1067,7 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         // Try to mark default before deadline
         client.mark_default(&loan_id);
@@ -770,6 +1082,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -777,7 +1090,7 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         // Provide insufficient amount
         client.repay_loan(&loan_id, &1000);
@@ -792,6 +1105,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -800,7 +1114,8 @@ mod test {
         client.update_total_liquidity(&10000);
 
         let duration = 3600u64;
-        let (loan_id, interest_rate) = client.issue_loan(&1, &borrower, &lender, &1000, &duration);
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &duration);
 
         // Advance ledger time past deadline
         env.ledger().with_mut(|li| {
@@ -821,6 +1136,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -828,7 +1144,8 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, interest_rate) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         let interest = (1000 * interest_rate as i128) / 10000;
         let total_due = 1000 + interest;
@@ -847,6 +1164,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -855,7 +1173,8 @@ mod test {
         client.update_total_liquidity(&10000);
 
         let duration = 3600u64;
-        let (loan_id, interest_rate) = client.issue_loan(&1, &borrower, &lender, &1000, &duration);
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &duration);
 
         let interest = (1000 * interest_rate as i128) / 10000;
         let total_due = 1000 + interest;
@@ -920,6 +1239,28 @@ mod test {
         assert_eq!(stored_engine, Some(risk_engine));
     }
 
+    #[test]
+    fn test_set_governance() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let governance = Address::generate(&env);
+        let successor = Address::generate(&env);
+
+        let contract_id = env.register(LoanManagement, ());
+        let client = LoanManagementClient::new(&env, &contract_id);
+
+        client.initialize(&admin);
+        client.set_governance(&governance);
+        assert_eq!(env.auths()[0].0, admin);
+
+        // Once set, only governance can hand over to a successor
+        client.set_governance(&successor);
+        assert_eq!(env.auths()[0].0, governance);
+        assert_eq!(client.get_governance(), Some(successor));
+    }
+
     #[test]
     fn test_mark_liquidated_success() {
         let env = Env::default();
@@ -928,7 +1269,8 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
-        let risk_engine = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let risk_engine = env.register(MockRiskEngine, ());
         let liquidator = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
@@ -938,7 +1280,7 @@ mod test {
         client.set_risk_engine(&risk_engine);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         client.mark_liquidated(&loan_id, &liquidator);
 
@@ -946,6 +1288,41 @@ mod test {
         assert_eq!(loan.status, LoanStatus::Liquidated);
     }
 
+    #[test]
+    fn test_repay_after_partial_liquidation() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let risk_engine = env.register(MockRiskEngine, ());
+
+        let contract_id = env.register(LoanManagement, ());
+        let client = LoanManagementClient::new(&env, &contract_id);
+
+        client.initialize(&admin);
+        client.set_risk_engine(&risk_engine);
+        client.update_total_liquidity(&10000);
+
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
+        let interest = (1000 * interest_rate as i128) / 10000;
+
+        // A liquidator covered 400 of the debt
+        client.record_liquidation_payment(&loan_id, &400);
+        assert_eq!(client.get_liquidation_paid(&loan_id), 400);
+
+        assert!(client
+            .try_repay_loan(&loan_id, &(600 + interest - 1))
+            .is_err());
+        client.repay_loan(&loan_id, &(600 + interest));
+
+        let loan = client.get_loan(&loan_id).unwrap();
+        assert_eq!(loan.status, LoanStatus::Repaid);
+    }
+
     #[test]
     #[should_panic(expected = "HostError: Error(Contract, #1)")]
     fn test_mark_liquidated_no_risk_engine() {
@@ -955,6 +1332,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
         let liquidator = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
@@ -963,7 +1341,7 @@ mod test {
         client.initialize(&admin);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, _) = client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         // Should fail - no risk engine set
         client.mark_liquidated(&loan_id, &liquidator);
@@ -978,7 +1356,8 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
-        let risk_engine = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let risk_engine = env.register(MockRiskEngine, ());
         let liquidator = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
@@ -988,7 +1367,8 @@ mod test {
         client.set_risk_engine(&risk_engine);
         client.update_total_liquidity(&10000);
 
-        let (loan_id, interest_rate) = client.issue_loan(&1, &borrower, &lender, &1000, &3600);
+        let (loan_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
 
         // Repay the loan first
         let interest = (1000 * interest_rate as i128) / 10000;
@@ -1088,6 +1468,7 @@ mod test {
         let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -1096,7 +1477,7 @@ mod test {
         client.update_total_liquidity(&10000);
 
         // Issue first loan
-        let (loan_id_1, _) = client.issue_loan(&1, &borrower, &lender, &2000, &3600);
+        let (loan_id_1, _) = client.issue_loan(&1, &borrower, &lender, &asset, &2000, &3600);
 
         let (total_liq, total_bor, util_bps) = client.get_utilization_stats();
         assert_eq!(total_liq, 10000);
@@ -1104,7 +1485,7 @@ mod test {
         assert_eq!(util_bps, 2000); // 20%
 
         // Issue second loan
-        let (_, _) = client.issue_loan(&2, &borrower, &lender, &3000, &3600);
+        let (_, _) = client.issue_loan(&2, &borrower, &lender, &asset, &3000, &3600);
 
         let (_, total_bor_2, util_bps_2) = client.get_utilization_stats();
         assert_eq!(total_bor_2, 5000);
@@ -1129,6 +1510,7 @@ mod test {
         let borrower1 = Address::generate(&env);
         let borrower2 = Address::generate(&env);
         let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
 
         let contract_id = env.register(LoanManagement, ());
         let client = LoanManagementClient::new(&env, &contract_id);
@@ -1137,10 +1519,10 @@ mod test {
         client.update_total_liquidity(&10000);
 
         // First loan at low utilization
-        let (_, rate1) = client.issue_loan(&1, &borrower1, &lender, &1000, &3600);
+        let (_, rate1) = client.issue_loan(&1, &borrower1, &lender, &asset, &1000, &3600);
 
         // Second loan at higher utilization
-        let (_, rate2) = client.issue_loan(&2, &borrower2, &lender, &3000, &3600);
+        let (_, rate2) = client.issue_loan(&2, &borrower2, &lender, &asset, &3000, &3600);
 
         // Rate should increase with utilization
         assert!(rate2 > rate1);
@@ -1195,4 +1577,41 @@ mod test {
         // base_rate (200) + risk_premium * risk_factor (100 * 1) = 300
         assert_eq!(rate, 300);
     }
+
+    #[test]
+    fn test_risk_engine_tracks_positions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let asset = Address::generate(&env);
+        let risk_engine = env.register(MockRiskEngine, ());
+        let risk_client = MockRiskEngineClient::new(&env, &risk_engine);
+
+        let contract_id = env.register(LoanManagement, ());
+        let client = LoanManagementClient::new(&env, &contract_id);
+
+        client.initialize(&admin);
+        client.set_risk_engine(&risk_engine);
+        client.update_total_liquidity(&10000);
+
+        // Issuing registers the escrow as an active position
+        let (repaid_id, interest_rate) =
+            client.issue_loan(&1, &borrower, &lender, &asset, &1000, &3600);
+        let (_, _) = client.issue_loan(&2, &borrower, &lender, &asset, &1000, &3600);
+        assert!(risk_client.is_tracked(&1));
+        assert!(risk_client.is_tracked(&2));
+
+        // Repayment removes it
+        let total_due = 1000 + (1000 * interest_rate as i128) / 10000;
+        client.repay_loan(&repaid_id, &total_due);
+        assert!(!risk_client.is_tracked(&1));
+
+        // So does a default
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3601);
+        client.mark_default(&2);
+        assert!(!risk_client.is_tracked(&2));
+    }
 }
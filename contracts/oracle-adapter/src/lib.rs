@@ -6,7 +6,7 @@
 
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, contracterror, symbol_short, Address, Bytes, BytesN, Env, Symbol, Vec};
+use soroban_sdk::{contract, contractclient, contractimpl, contracttype, contracterror, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
 
 #[contracterror]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -19,6 +19,14 @@ pub enum ContractError {
     ConfirmationAlreadyExists = 6,
     EscrowNotFound = 7,
     InvalidEventType = 8,
+    ConditionAlreadyRegistered = 9,
+    InvalidQuorum = 10,
+    ConditionNotFound = 11,
+    StaleSequence = 12,
+    ConfirmationNotFound = 13,
+    OracleInactive = 14,
+    EventTypeNotSupported = 15,
+    InvalidPayload = 16,
 }
 
 /// Event types for oracle confirmations
@@ -32,16 +40,154 @@ pub enum EventType {
     Valuation = 5,
 }
 
+/// Shipment milestone reported by a carrier oracle
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ShipmentStatus {
+    Booked = 0,
+    Departed = 1,
+    InTransit = 2,
+    Arrived = 3,
+    Delivered = 4,
+}
+
+/// Shipment confirmation payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShipmentPayload {
+    /// SCAC or IATA carrier code
+    pub carrier_code: Symbol,
+    /// SHA-256 hash of the tracking number
+    pub tracking_hash: BytesN<32>,
+    /// UN/LOCODE of the reporting port
+    pub port: Symbol,
+    pub status: ShipmentStatus,
+}
+
+/// Delivery confirmation payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeliveryPayload {
+    /// SHA-256 hash of the receiver's signed delivery note
+    pub receiver_signoff_hash: BytesN<32>,
+}
+
+/// Quality inspection payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QualityPayload {
+    /// Inspection grade on a 0-100 scale
+    pub grade: u32,
+    /// SHA-256 hash of the inspection certificate
+    pub inspection_cert_hash: BytesN<32>,
+}
+
+/// Valuation payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValuationPayload {
+    pub amount: i128,
+    /// ISO 4217 currency code
+    pub currency: Symbol,
+}
+
+/// Typed confirmation payload, one variant per event type
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfirmationPayload {
+    Shipment(ShipmentPayload),
+    Delivery(DeliveryPayload),
+    Quality(QualityPayload),
+    Custom(Bytes),
+    Valuation(ValuationPayload),
+}
+
 /// Oracle confirmation data structure
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct ConfirmationData {
+    /// Escrow contract the confirmation is scoped to
+    pub escrow_contract: Address,
     pub escrow_id: Bytes,
     pub event_type: u32,
-    pub result: Bytes,
+    pub payload: ConfirmationPayload,
     pub oracle: Address,
     pub timestamp: u64,
     pub verified: bool,
+    /// Attestation sequence per (escrow, oracle, event type); higher replaces lower
+    pub sequence: u64,
+    /// Ledger timestamp after which the confirmation is stale (0 = never)
+    pub expires_at: u64,
+    /// Whether the payload matches the majority payload of the live
+    /// confirmations for the event type (None while no payload holds a
+    /// strict majority). Re-scored whenever those confirmations change.
+    pub agreed: Option<bool>,
+    /// `added_at` of the oracle registration that submitted the confirmation;
+    /// confirmations from an earlier registration no longer count
+    pub oracle_added_at: u64,
+}
+
+/// On-chain reputation counters for an oracle
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OracleStats {
+    /// Confirmations submitted, including re-attestations
+    pub confirmations: u32,
+    /// Live confirmations that match the current majority payload
+    pub agreements: u32,
+    /// Live confirmations that differ from the current majority payload
+    pub disagreements: u32,
+    /// Challenges upheld against the oracle's confirmations
+    pub challenges_lost: u32,
+    /// Ledger timestamp of the oracle's last confirmation
+    pub last_active: u64,
+}
+
+/// Release condition registered by an escrow contract
+///
+/// Conditions and confirmations are keyed by `(escrow_contract, escrow_id)`,
+/// so one escrow contract cannot claim or satisfy another's escrow ids.
+/// Once every event type in `event_types` has at least `quorum` oracle
+/// confirmations, the adapter calls `release_funds(escrow_ref)` on
+/// `escrow_contract`.
+///
+/// A milestone condition instead treats `event_types` as ordered milestones
+/// and calls `release_milestone(escrow_ref, event_type)` for each one as soon
+/// as it and every earlier milestone reach the quorum.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ReleaseCondition {
+    pub escrow_contract: Address,
+    pub escrow_ref: u64,
+    pub event_types: Vec<u32>,
+    pub quorum: u32,
+    pub released: bool,
+    /// Whether each event type releases its own milestone tranche
+    pub milestones: bool,
+    /// Number of milestones released so far (milestone conditions only)
+    pub milestones_released: u32,
+}
+
+/// Escrow contract interface invoked when a release condition is met
+#[contractclient(name = "EscrowContractClient")]
+pub trait EscrowContract {
+    fn release_funds(env: Env, escrow_id: u64);
+    fn release_milestone(env: Env, escrow_id: u64, event_type: u32) -> i128;
+}
+
+/// Registered oracle metadata, stored per oracle in persistent storage
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleInfo {
+    pub address: Address,
+    /// The oracle's ed25519 public key
+    pub public_key: BytesN<32>,
+    /// Event types the oracle may confirm (empty = all)
+    pub event_types: Vec<u32>,
+    pub added_at: u64,
+    pub active: bool,
+    /// Position in the oracle index used for paginated listing
+    pub index: u32,
 }
 
 /// Contract data structure for storage
@@ -50,14 +196,24 @@ pub struct ConfirmationData {
 pub struct ContractData {
     pub admin: Address,
     pub initialized: bool,
-    pub oracles: Vec<Address>,
 }
 
+/// Maximum number of entries returned by paginated queries
+const MAX_PAGE_SIZE: u32 = 50;
+
 /// Event symbols
 const ORACLE_ADDED: Symbol = symbol_short!("orc_add");
 const ORACLE_REMOVED: Symbol = symbol_short!("orc_rem");
+const ORACLE_STATUS: Symbol = symbol_short!("orc_stat");
 const ORACLE_CONFIRMED: Symbol = symbol_short!("confirmed");
 const INITIALIZED: Symbol = symbol_short!("init");
+const CONDITION_SET: Symbol = symbol_short!("cond_set");
+const RELEASE_TRIGGERED: Symbol = symbol_short!("rel_trig");
+const RELEASE_FAILED: Symbol = symbol_short!("rel_fail");
+const MILESTONE_RELEASED: Symbol = symbol_short!("ms_trig");
+const VALIDITY_SET: Symbol = symbol_short!("valid_set");
+const CHALLENGE_RESOLVED: Symbol = symbol_short!("chal_res");
+const STATS_UPDATED: Symbol = symbol_short!("orc_rep");
 
 /// Main contract for oracle adapter operations
 #[contract]
@@ -83,7 +239,6 @@ impl OracleAdapter {
         let contract_data = ContractData {
             admin: admin.clone(),
             initialized: true,
-            oracles: Vec::new(&env),
         };
 
         env.storage().instance().set(&symbol_short!("data"), &contract_data);
@@ -97,33 +252,57 @@ impl OracleAdapter {
     ///
     /// # Arguments
     /// * `oracle` - The oracle address to add
+    /// * `public_key` - The oracle's ed25519 public key
+    /// * `event_types` - Event types the oracle may confirm (empty = all)
     ///
     /// # Events
     /// Emits `ORACLE_ADDED` event
-    pub fn add_oracle(env: Env, oracle: Address) -> Result<(), ContractError> {
+    pub fn add_oracle(
+        env: Env,
+        oracle: Address,
+        public_key: BytesN<32>,
+        event_types: Vec<u32>,
+    ) -> Result<(), ContractError> {
         Self::check_admin(&env)?;
 
-        let mut contract_data = Self::get_contract_data(&env)?;
-
         // Check if oracle is already registered
-        if Self::is_oracle_registered(&contract_data, &oracle) {
+        if Self::get_oracle_info(&env, &oracle).is_some() {
             return Err(ContractError::OracleAlreadyRegistered);
         }
 
-        // Add oracle to registry
-        contract_data.oracles.push_back(oracle.clone());
+        for event_type in event_types.iter() {
+            if !Self::is_valid_event_type(event_type) {
+                return Err(ContractError::InvalidEventType);
+            }
+        }
+
+        // Append oracle to the index
+        let index = Self::oracle_count(&env);
+        let info = OracleInfo {
+            address: oracle.clone(),
+            public_key,
+            event_types: event_types.clone(),
+            added_at: env.ledger().timestamp(),
+            active: true,
+            index,
+        };
 
-        // Save updated data
-        env.storage().instance().set(&symbol_short!("data"), &contract_data);
+        env.storage().persistent().set(&(symbol_short!("oracle"), oracle.clone()), &info);
+        env.storage().persistent().set(&(symbol_short!("orc_idx"), index), &oracle);
+        env.storage().instance().set(&symbol_short!("orc_cnt"), &(index + 1));
+        Self::set_active_oracle_count(&env, Self::active_oracle_count(&env) + 1);
 
         // Emit event
-        env.events().publish((ORACLE_ADDED,), (oracle,));
+        env.events().publish((ORACLE_ADDED,), (oracle, event_types));
 
         Ok(())
     }
 
     /// Remove an oracle from the registry (admin only)
     ///
+    /// The last oracle in the index takes the removed oracle's slot. The
+    /// oracle's confirmations stop counting, even if it is added again later.
+    ///
     /// # Arguments
     /// * `oracle` - The oracle address to remove
     ///
@@ -132,114 +311,396 @@ impl OracleAdapter {
     pub fn remove_oracle(env: Env, oracle: Address) -> Result<(), ContractError> {
         Self::check_admin(&env)?;
 
-        let mut contract_data = Self::get_contract_data(&env)?;
-
-        // Find and remove oracle
-        let mut found = false;
-        let mut new_oracles = Vec::new(&env);
-
-        for existing_oracle in contract_data.oracles.iter() {
-            if existing_oracle != oracle {
-                new_oracles.push_back(existing_oracle);
-            } else {
-                found = true;
-            }
+        let info = Self::get_oracle_info(&env, &oracle).ok_or(ContractError::OracleNotRegistered)?;
+
+        // Move the last oracle into the freed index slot
+        let last_index = Self::oracle_count(&env) - 1;
+        if info.index != last_index {
+            let last_oracle: Address = env
+                .storage()
+                .persistent()
+                .get(&(symbol_short!("orc_idx"), last_index))
+                .ok_or(ContractError::OracleNotRegistered)?;
+            let mut last_info = Self::get_oracle_info(&env, &last_oracle).ok_or(ContractError::OracleNotRegistered)?;
+            last_info.index = info.index;
+            env.storage().persistent().set(&(symbol_short!("oracle"), last_oracle.clone()), &last_info);
+            env.storage().persistent().set(&(symbol_short!("orc_idx"), info.index), &last_oracle);
         }
 
-        if !found {
-            return Err(ContractError::OracleNotRegistered);
+        env.storage().persistent().remove(&(symbol_short!("orc_idx"), last_index));
+        env.storage().persistent().remove(&(symbol_short!("oracle"), oracle.clone()));
+        env.storage().instance().set(&symbol_short!("orc_cnt"), &last_index);
+        if info.active {
+            Self::set_active_oracle_count(&env, Self::active_oracle_count(&env) - 1);
         }
 
-        contract_data.oracles = new_oracles;
-
-        // Save updated data
-        env.storage().instance().set(&symbol_short!("data"), &contract_data);
-
         // Emit event
         env.events().publish((ORACLE_REMOVED,), (oracle,));
 
         Ok(())
     }
 
+    /// Activate or suspend a registered oracle (admin only)
+    ///
+    /// Suspended oracles cannot confirm events and their confirmations
+    /// do not count towards release conditions.
+    ///
+    /// # Events
+    /// Emits `ORACLE_STATUS` event
+    pub fn set_oracle_active(env: Env, oracle: Address, active: bool) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+
+        let mut info = Self::get_oracle_info(&env, &oracle).ok_or(ContractError::OracleNotRegistered)?;
+        if info.active != active {
+            let count = Self::active_oracle_count(&env);
+            Self::set_active_oracle_count(&env, if active { count + 1 } else { count - 1 });
+        }
+        info.active = active;
+        env.storage().persistent().set(&(symbol_short!("oracle"), oracle.clone()), &info);
+
+        env.events().publish((ORACLE_STATUS,), (oracle, active));
+
+        Ok(())
+    }
+
     /// Confirm an event with oracle signature verification
     ///
+    /// An oracle holds one confirmation per escrow and event type. A later
+    /// attestation with a higher `sequence` replaces the stored one, which
+    /// lets oracles refresh perishable readings such as cold-chain temperature.
+    ///
     /// # Arguments
+    /// * `escrow_contract` - The escrow contract the confirmation is scoped to
     /// * `escrow_id` - The escrow ID to confirm
-    /// * `event_type` - Type of event (1=Shipment, 2=Delivery, 3=Quality, 4=Custom)
-    /// * `result` - The confirmation result data
+    /// * `event_type` - Type of event (1=Shipment, 2=Delivery, 3=Quality, 4=Custom, 5=Valuation)
+    /// * `sequence` - Attestation sequence number, must exceed the stored one
+    /// * `payload` - Typed confirmation payload matching `event_type`
     /// * `signature` - Oracle signature for verification
     ///
     /// # Events
     /// Emits `ORACLE_CONFIRMED` event
+    #[allow(clippy::too_many_arguments)]
     pub fn confirm_event(
         env: Env,
         oracle: Address,
+        escrow_contract: Address,
         escrow_id: Bytes,
         event_type: u32,
-        result: Bytes,
+        sequence: u64,
+        payload: ConfirmationPayload,
         signature: Bytes,
     ) -> Result<(), ContractError> {
-        let contract_data = Self::get_contract_data(&env)?;
+        Self::get_contract_data(&env)?;
 
-        // Verify oracle is registered
-        if !Self::is_oracle_registered(&contract_data, &oracle) {
-            return Err(ContractError::OracleNotRegistered);
+        // Verify oracle is registered and active
+        let info = Self::get_oracle_info(&env, &oracle).ok_or(ContractError::OracleNotRegistered)?;
+        if !info.active {
+            return Err(ContractError::OracleInactive);
         }
 
         // Validate event type
-        if event_type < 1 || event_type > 5 {
+        if !Self::is_valid_event_type(event_type) {
             return Err(ContractError::InvalidEventType);
         }
+        if !info.event_types.is_empty() && !info.event_types.contains(event_type) {
+            return Err(ContractError::EventTypeNotSupported);
+        }
 
-        // Check if confirmation already exists (prevent replay)
-        let confirmation_key = (escrow_id.clone(), oracle.clone());
-        if env.storage().persistent().has(&confirmation_key) {
-            return Err(ContractError::ConfirmationAlreadyExists);
+        // Validate payload shape against the event type
+        Self::validate_payload(event_type, &payload)?;
+
+        // Only a newer attestation may replace an existing one (prevent replay).
+        // A confirmation from an earlier registration of the oracle is void.
+        let confirmation_key = (escrow_contract.clone(), escrow_id.clone(), oracle.clone(), event_type);
+        let existing: Option<ConfirmationData> = env.storage().persistent().get(&confirmation_key);
+        if let Some(existing) = existing.as_ref().filter(|existing| existing.oracle_added_at == info.added_at) {
+            if sequence == existing.sequence {
+                return Err(ContractError::ConfirmationAlreadyExists);
+            }
+            if sequence < existing.sequence {
+                return Err(ContractError::StaleSequence);
+            }
         }
 
         // Create message for signature verification
-        let message = Self::create_message(&env, &escrow_id, event_type, &result);
+        let message = Self::create_message(&env, &escrow_contract, &escrow_id, event_type, sequence, &payload);
 
         // Verify signature
         Self::verify_signature(&env, &message, &signature, &oracle)?;
 
+        // Apply the validity period configured for this event type
+        let timestamp = env.ledger().timestamp();
+        let validity_period = Self::get_validity_period(env.clone(), event_type);
+        let expires_at = if validity_period == 0 {
+            0
+        } else {
+            timestamp.saturating_add(validity_period)
+        };
+
         // Create confirmation data
         let confirmation = ConfirmationData {
+            escrow_contract: escrow_contract.clone(),
             escrow_id: escrow_id.clone(),
             event_type,
-            result: result.clone(),
+            payload: payload.clone(),
             oracle: oracle.clone(),
-            timestamp: env.ledger().timestamp(),
+            timestamp,
             verified: true,
+            sequence,
+            expires_at,
+            agreed: None,
+            oracle_added_at: info.added_at,
         };
 
         // Store confirmation
         env.storage().persistent().set(&confirmation_key, &confirmation);
 
+        // Index the oracle under (escrow, event type) on its first attestation
+        if existing.is_none() {
+            let index_key = (symbol_short!("evt_orcs"), escrow_contract.clone(), escrow_id.clone(), event_type);
+            let mut oracles: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&index_key)
+                .unwrap_or(Vec::new(&env));
+            oracles.push_back(oracle.clone());
+            env.storage().persistent().set(&index_key, &oracles);
+        }
+
+        // Update reputation counters, dropping the score of a re-attested confirmation
+        let mut stats = Self::get_oracle_stats(env.clone(), oracle.clone());
+        if let Some(existing) = &existing {
+            Self::unscore(&mut stats, existing);
+        }
+        stats.confirmations += 1;
+        stats.last_active = timestamp;
+        Self::set_oracle_stats(&env, &oracle, &stats);
+
+        // Score every live confirmation, this one included, against the new majority
+        Self::rescore(&env, &escrow_contract, &escrow_id, event_type);
+
         // Emit event
         env.events().publish(
             (ORACLE_CONFIRMED,),
-            (escrow_id, event_type, result, oracle),
+            (escrow_contract.clone(), escrow_id.clone(), event_type, payload, oracle, sequence),
+        );
+
+        // Release escrow funds if this confirmation completes the condition
+        Self::try_release(&env, &escrow_contract, &escrow_id);
+
+        Ok(())
+    }
+
+    /// Set how long confirmations of an event type stay valid (admin only)
+    ///
+    /// # Arguments
+    /// * `event_type` - The event type to configure
+    /// * `period` - Validity period in seconds (0 = never expires)
+    ///
+    /// # Events
+    /// Emits `VALIDITY_SET` event
+    pub fn set_validity_period(env: Env, event_type: u32, period: u64) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+
+        if !Self::is_valid_event_type(event_type) {
+            return Err(ContractError::InvalidEventType);
+        }
+
+        env.storage()
+            .instance()
+            .set(&(symbol_short!("validity"), event_type), &period);
+
+        env.events().publish((VALIDITY_SET,), (event_type, period));
+
+        Ok(())
+    }
+
+    /// Resolve a challenge against an oracle's confirmation (admin only)
+    ///
+    /// An upheld challenge removes the confirmation, reverses its agreement
+    /// score and counts as a lost challenge in the oracle's stats. The
+    /// remaining confirmations are re-scored against the new majority.
+    ///
+    /// # Arguments
+    /// * `escrow_contract` - The escrow contract the confirmation is scoped to
+    /// * `escrow_id` - The escrow ID of the challenged confirmation
+    /// * `oracle` - The oracle that submitted it
+    /// * `event_type` - The event type of the challenged confirmation
+    /// * `upheld` - true if the challenge succeeded against the oracle
+    ///
+    /// # Events
+    /// Emits `CHALLENGE_RESOLVED` event
+    pub fn resolve_challenge(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        oracle: Address,
+        event_type: u32,
+        upheld: bool,
+    ) -> Result<(), ContractError> {
+        Self::check_admin(&env)?;
+
+        let confirmation_key = (escrow_contract.clone(), escrow_id.clone(), oracle.clone(), event_type);
+        let confirmation: ConfirmationData = env
+            .storage()
+            .persistent()
+            .get(&confirmation_key)
+            .ok_or(ContractError::ConfirmationNotFound)?;
+
+        if upheld {
+            env.storage().persistent().remove(&confirmation_key);
+
+            let index_key = (symbol_short!("evt_orcs"), escrow_contract.clone(), escrow_id.clone(), event_type);
+            let oracles: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&index_key)
+                .unwrap_or(Vec::new(&env));
+            if let Some(index) = oracles.first_index_of(&oracle) {
+                let mut oracles = oracles;
+                oracles.remove(index);
+                env.storage().persistent().set(&index_key, &oracles);
+            }
+
+            let mut stats = Self::get_oracle_stats(env.clone(), oracle.clone());
+            Self::unscore(&mut stats, &confirmation);
+            stats.challenges_lost += 1;
+            Self::set_oracle_stats(&env, &oracle, &stats);
+
+            Self::rescore(&env, &escrow_contract, &escrow_id, event_type);
+        }
+
+        env.events().publish(
+            (CHALLENGE_RESOLVED,),
+            (escrow_contract, escrow_id, oracle, event_type, upheld),
         );
 
         Ok(())
     }
 
+    /// Get reputation counters for an oracle
+    pub fn get_oracle_stats(env: Env, oracle: Address) -> OracleStats {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("stats"), oracle))
+            .unwrap_or_default()
+    }
+
+    /// Get the validity period in seconds for an event type (0 = never expires)
+    pub fn get_validity_period(env: Env, event_type: u32) -> u64 {
+        env.storage()
+            .instance()
+            .get(&(symbol_short!("validity"), event_type))
+            .unwrap_or(0)
+    }
+
+    /// Register a release condition for an escrow
+    ///
+    /// # Arguments
+    /// * `escrow_contract` - The escrow contract to call back (must authorize)
+    /// * `escrow_id` - The escrow ID oracles confirm events against, scoped to `escrow_contract`
+    /// * `escrow_ref` - The escrow ID passed to `release_funds` on the escrow contract
+    /// * `event_types` - Event types that must all be confirmed
+    /// * `quorum` - Number of oracle confirmations required per event type
+    ///
+    /// # Events
+    /// Emits `CONDITION_SET` event
+    pub fn register_release_condition(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        escrow_ref: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+    ) -> Result<(), ContractError> {
+        Self::register_condition(&env, escrow_contract, escrow_id, escrow_ref, event_types, quorum, false)
+    }
+
+    /// Register a milestone release condition for an escrow
+    ///
+    /// Each event type is a milestone, in payout order. Once a milestone and
+    /// all earlier ones have `quorum` confirmations, the adapter calls
+    /// `release_milestone(escrow_ref, event_type)` on the escrow contract.
+    ///
+    /// # Arguments
+    /// * `escrow_contract` - The escrow contract to call back (must authorize)
+    /// * `escrow_id` - The escrow ID oracles confirm events against, scoped to `escrow_contract`
+    /// * `escrow_ref` - The escrow ID passed to `release_milestone` on the escrow contract
+    /// * `event_types` - Milestone event types, in order
+    /// * `quorum` - Number of oracle confirmations required per milestone
+    ///
+    /// # Events
+    /// Emits `CONDITION_SET` event
+    pub fn register_milestone_condition(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        escrow_ref: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+    ) -> Result<(), ContractError> {
+        Self::register_condition(&env, escrow_contract, escrow_id, escrow_ref, event_types, quorum, true)
+    }
+
+    /// Retry the release callback for an escrow whose condition is met
+    ///
+    /// Useful when the escrow contract rejected an earlier callback.
+    ///
+    /// # Returns
+    /// true if the escrow contract released the funds, or for a milestone
+    /// condition at least one tranche
+    pub fn trigger_release(env: Env, escrow_contract: Address, escrow_id: Bytes) -> Result<bool, ContractError> {
+        if Self::get_release_condition(env.clone(), escrow_contract.clone(), escrow_id.clone()).is_none() {
+            return Err(ContractError::ConditionNotFound);
+        }
+        Ok(Self::try_release(&env, &escrow_contract, &escrow_id))
+    }
+
+    /// Get the release condition an escrow contract registered for an escrow
+    pub fn get_release_condition(env: Env, escrow_contract: Address, escrow_id: Bytes) -> Option<ReleaseCondition> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("rel_cond"), escrow_contract, escrow_id))
+    }
+
+    /// Get the number of confirmations for an event type on an escrow
+    /// that are still valid at the current ledger time
+    pub fn get_confirmation_count(env: Env, escrow_contract: Address, escrow_id: Bytes, event_type: u32) -> u32 {
+        Self::get_valid_confirmations(&env, &escrow_contract, &escrow_id, event_type).len()
+    }
+
+    /// Get the payload most valid confirmations for an event type on an
+    /// escrow agree on, and how many carry it
+    ///
+    /// # Returns
+    /// None when there are no valid confirmations or several payloads
+    /// share the top count
+    pub fn get_majority_confirmation(
+        env: Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        event_type: u32,
+    ) -> Option<(ConfirmationPayload, u32)> {
+        let confirmations = Self::get_valid_confirmations(&env, &escrow_contract, &escrow_id, event_type);
+        let (majority, count) = Self::majority_payload(&confirmations);
+        majority.map(|payload| (payload, count))
+    }
+
     /// Get confirmation data for an escrow
     ///
+    /// Only confirmations still valid at the current ledger time are returned.
+    ///
     /// # Arguments
+    /// * `escrow_contract` - The escrow contract the confirmations are scoped to
     /// * `escrow_id` - The escrow ID to query
     ///
     /// # Returns
     /// Option containing confirmation data if found
-    pub fn get_confirmation(env: Env, escrow_id: Bytes) -> Option<Vec<ConfirmationData>> {
-        let contract_data = Self::get_contract_data(&env).ok()?;
+    pub fn get_confirmation(env: Env, escrow_contract: Address, escrow_id: Bytes) -> Option<Vec<ConfirmationData>> {
         let mut confirmations = Vec::new(&env);
 
-        // Iterate through all registered oracles
-        for oracle in contract_data.oracles.iter() {
-            let confirmation_key = (escrow_id.clone(), oracle.clone());
-            if let Some(confirmation) = env.storage().persistent().get(&confirmation_key) {
+        for event_type in 1..=5u32 {
+            for confirmation in Self::get_valid_confirmations(&env, &escrow_contract, &escrow_id, event_type).iter() {
                 confirmations.push_back(confirmation);
             }
         }
@@ -259,14 +720,19 @@ impl OracleAdapter {
     /// # Returns
     /// true if oracle is registered, false otherwise
     pub fn is_oracle_registered_query(env: Env, oracle: Address) -> Result<bool, ContractError> {
-        let contract_data = Self::get_contract_data(&env)?;
-        Ok(Self::is_oracle_registered(&contract_data, &oracle))
+        Self::get_contract_data(&env)?;
+        Ok(Self::get_oracle_info(&env, &oracle).is_some())
+    }
+
+    /// Get metadata for a registered oracle
+    pub fn get_oracle(env: Env, oracle: Address) -> Option<OracleInfo> {
+        Self::get_oracle_info(&env, &oracle)
     }
 
     /// Get the total number of registered oracles
     pub fn get_oracle_count(env: Env) -> Result<u32, ContractError> {
-        let contract_data = Self::get_contract_data(&env)?;
-        Ok(contract_data.oracles.len())
+        Self::get_contract_data(&env)?;
+        Ok(Self::oracle_count(&env))
     }
 
     /// Get oracle address at specific index
@@ -277,7 +743,29 @@ impl OracleAdapter {
     /// # Returns
     /// Oracle address at the given index
     pub fn get_oracle_at(env: Env, index: u32) -> Option<Address> {
-        Self::get_contract_data(&env).ok()?.oracles.get(index)
+        env.storage().persistent().get(&(symbol_short!("orc_idx"), index))
+    }
+
+    /// List registered oracles
+    ///
+    /// # Arguments
+    /// * `start` - Index of the first oracle to return
+    /// * `limit` - Maximum number of oracles to return (capped at 50)
+    pub fn list_oracles(env: Env, start: u32, limit: u32) -> Vec<OracleInfo> {
+        let mut oracles = Vec::new(&env);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::oracle_count(&env));
+
+        for index in start..end {
+            if let Some(oracle) = Self::get_oracle_at(env.clone(), index) {
+                if let Some(info) = Self::get_oracle_info(&env, &oracle) {
+                    oracles.push_back(info);
+                }
+            }
+        }
+
+        oracles
     }
 
     /// Get admin address
@@ -304,21 +792,333 @@ impl OracleAdapter {
         Ok(())
     }
 
-    fn is_oracle_registered(contract_data: &ContractData, oracle: &Address) -> bool {
-        for registered_oracle in contract_data.oracles.iter() {
-            if registered_oracle == *oracle {
-                return true;
+    fn get_oracle_info(env: &Env, oracle: &Address) -> Option<OracleInfo> {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("oracle"), oracle.clone()))
+    }
+
+    fn oracle_count(env: &Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("orc_cnt")).unwrap_or(0)
+    }
+
+    fn active_oracle_count(env: &Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("act_cnt")).unwrap_or(0)
+    }
+
+    fn set_active_oracle_count(env: &Env, count: u32) {
+        env.storage().instance().set(&symbol_short!("act_cnt"), &count);
+    }
+
+    fn is_valid_event_type(event_type: u32) -> bool {
+        (1..=5).contains(&event_type)
+    }
+
+    fn is_expired(env: &Env, confirmation: &ConfirmationData) -> bool {
+        confirmation.expires_at != 0 && env.ledger().timestamp() > confirmation.expires_at
+    }
+
+    fn get_valid_confirmations(env: &Env, escrow_contract: &Address, escrow_id: &Bytes, event_type: u32) -> Vec<ConfirmationData> {
+        let mut confirmations = Vec::new(env);
+        let oracles: Vec<Address> = env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("evt_orcs"), escrow_contract.clone(), escrow_id.clone(), event_type))
+            .unwrap_or(Vec::new(env));
+
+        for oracle in oracles.iter() {
+            // Removed or suspended oracles no longer count
+            let info = match Self::get_oracle_info(env, &oracle) {
+                Some(info) if info.active => info,
+                _ => continue,
+            };
+            let confirmation_key = (escrow_contract.clone(), escrow_id.clone(), oracle, event_type);
+            if let Some(confirmation) = env.storage().persistent().get::<_, ConfirmationData>(&confirmation_key) {
+                // Neither do confirmations from before the oracle was re-added
+                if confirmation.oracle_added_at == info.added_at && !Self::is_expired(env, &confirmation) {
+                    confirmations.push_back(confirmation);
+                }
+            }
+        }
+
+        confirmations
+    }
+
+    /// A condition is met when, for every event type, at least `quorum`
+    /// valid confirmations carry the same payload
+    fn is_condition_met(env: &Env, escrow_id: &Bytes, condition: &ReleaseCondition) -> bool {
+        for event_type in condition.event_types.iter() {
+            let confirmations = Self::get_valid_confirmations(env, &condition.escrow_contract, escrow_id, event_type);
+            let (_, count) = Self::majority_payload(&confirmations);
+            if count < condition.quorum {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Most common payload among confirmations and how many carry it.
+    /// The payload is None when several payloads share the top count, so
+    /// arrival order never decides the majority.
+    fn majority_payload(confirmations: &Vec<ConfirmationData>) -> (Option<ConfirmationPayload>, u32) {
+        let mut majority: Option<ConfirmationPayload> = None;
+        let mut majority_count = 0u32;
+        for candidate in confirmations.iter() {
+            let count = confirmations
+                .iter()
+                .filter(|c| c.payload == candidate.payload)
+                .count() as u32;
+            if count > majority_count {
+                majority_count = count;
+                majority = Some(candidate.payload.clone());
+            } else if count == majority_count && majority.as_ref() != Some(&candidate.payload) {
+                majority = None;
+            }
+        }
+        (majority, majority_count)
+    }
+
+    /// Re-score the live confirmations for an event type against their
+    /// current majority payload, updating the oracles' stats where a score
+    /// changes
+    fn rescore(env: &Env, escrow_contract: &Address, escrow_id: &Bytes, event_type: u32) {
+        let confirmations = Self::get_valid_confirmations(env, escrow_contract, escrow_id, event_type);
+        let (majority, _) = Self::majority_payload(&confirmations);
+
+        for mut confirmation in confirmations.iter() {
+            let agreed = majority.as_ref().map(|payload| *payload == confirmation.payload);
+            if agreed == confirmation.agreed {
+                continue;
+            }
+
+            let mut stats = Self::get_oracle_stats(env.clone(), confirmation.oracle.clone());
+            Self::unscore(&mut stats, &confirmation);
+            confirmation.agreed = agreed;
+            Self::score(&mut stats, &confirmation);
+            Self::set_oracle_stats(env, &confirmation.oracle, &stats);
+
+            let confirmation_key = (escrow_contract.clone(), escrow_id.clone(), confirmation.oracle.clone(), event_type);
+            env.storage().persistent().set(&confirmation_key, &confirmation);
+        }
+    }
+
+    /// Validate and store a release condition, then try it against
+    /// confirmations that may already have landed
+    fn register_condition(
+        env: &Env,
+        escrow_contract: Address,
+        escrow_id: Bytes,
+        escrow_ref: u64,
+        event_types: Vec<u32>,
+        quorum: u32,
+        milestones: bool,
+    ) -> Result<(), ContractError> {
+        escrow_contract.require_auth();
+
+        Self::get_contract_data(env)?;
+
+        // Suspended oracles cannot confirm, so they cannot help reach a quorum
+        if quorum == 0 || quorum > Self::active_oracle_count(env) {
+            return Err(ContractError::InvalidQuorum);
+        }
+
+        if event_types.is_empty() {
+            return Err(ContractError::InvalidEventType);
+        }
+        for event_type in event_types.iter() {
+            if !Self::is_valid_event_type(event_type) {
+                return Err(ContractError::InvalidEventType);
             }
         }
-        false
+
+        let condition_key = (symbol_short!("rel_cond"), escrow_contract.clone(), escrow_id.clone());
+        if env.storage().persistent().has(&condition_key) {
+            return Err(ContractError::ConditionAlreadyRegistered);
+        }
+
+        let condition = ReleaseCondition {
+            escrow_contract: escrow_contract.clone(),
+            escrow_ref,
+            event_types: event_types.clone(),
+            quorum,
+            released: false,
+            milestones,
+            milestones_released: 0,
+        };
+        env.storage().persistent().set(&condition_key, &condition);
+
+        env.events().publish(
+            (CONDITION_SET,),
+            (escrow_id.clone(), escrow_contract.clone(), event_types, quorum),
+        );
+
+        // Confirmations may already have landed before registration
+        Self::try_release(env, &escrow_contract, &escrow_id);
+
+        Ok(())
     }
 
-    fn create_message(env: &Env, escrow_id: &Bytes, event_type: u32, result: &Bytes) -> BytesN<32> {
+    /// Call the escrow's release entrypoint if its condition is met.
+    /// A rejected callback leaves the condition pending so it can be retried.
+    fn try_release(env: &Env, escrow_contract: &Address, escrow_id: &Bytes) -> bool {
+        let condition_key = (symbol_short!("rel_cond"), escrow_contract.clone(), escrow_id.clone());
+        let mut condition: ReleaseCondition = match env.storage().persistent().get(&condition_key) {
+            Some(condition) => condition,
+            None => return false,
+        };
+
+        if condition.released {
+            return false;
+        }
+
+        if condition.milestones {
+            return Self::release_milestones(env, escrow_id, &condition_key, condition);
+        }
+
+        if !Self::is_condition_met(env, escrow_id, &condition) {
+            return false;
+        }
+
+        // Mark released before the callback so it cannot be re-entered
+        condition.released = true;
+        env.storage().persistent().set(&condition_key, &condition);
+
+        let escrow_client = EscrowContractClient::new(env, &condition.escrow_contract);
+        if escrow_client.try_release_funds(&condition.escrow_ref).is_ok() {
+            env.events().publish(
+                (RELEASE_TRIGGERED,),
+                (escrow_id.clone(), condition.escrow_contract, condition.escrow_ref),
+            );
+            true
+        } else {
+            condition.released = false;
+            env.storage().persistent().set(&condition_key, &condition);
+            env.events().publish(
+                (RELEASE_FAILED,),
+                (escrow_id.clone(), condition.escrow_contract, condition.escrow_ref),
+            );
+            false
+        }
+    }
+
+    /// Release every milestone, in order, whose event has reached the quorum.
+    /// Stops at the first unconfirmed or rejected milestone.
+    fn release_milestones(
+        env: &Env,
+        escrow_id: &Bytes,
+        condition_key: &(Symbol, Address, Bytes),
+        mut condition: ReleaseCondition,
+    ) -> bool {
+        let escrow_client = EscrowContractClient::new(env, &condition.escrow_contract);
+        let mut released_any = false;
+
+        while let Some(event_type) = condition.event_types.get(condition.milestones_released) {
+            let confirmations = Self::get_valid_confirmations(env, &condition.escrow_contract, escrow_id, event_type);
+            let (_, count) = Self::majority_payload(&confirmations);
+            if count < condition.quorum {
+                break;
+            }
+
+            // Advance before the callback so it cannot be re-entered
+            let index = condition.milestones_released;
+            condition.milestones_released += 1;
+            condition.released = condition.milestones_released == condition.event_types.len();
+            env.storage().persistent().set(condition_key, &condition);
+
+            if escrow_client.try_release_milestone(&condition.escrow_ref, &event_type).is_err() {
+                condition.milestones_released = index;
+                condition.released = false;
+                env.storage().persistent().set(condition_key, &condition);
+                env.events().publish(
+                    (RELEASE_FAILED,),
+                    (escrow_id.clone(), condition.escrow_contract.clone(), condition.escrow_ref),
+                );
+                break;
+            }
+
+            env.events().publish(
+                (MILESTONE_RELEASED,),
+                (escrow_id.clone(), condition.escrow_contract.clone(), condition.escrow_ref, index),
+            );
+            released_any = true;
+        }
+
+        released_any
+    }
+
+    fn set_oracle_stats(env: &Env, oracle: &Address, stats: &OracleStats) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("stats"), oracle.clone()), stats);
+
+        env.events().publish(
+            (STATS_UPDATED,),
+            (
+                oracle.clone(),
+                stats.confirmations,
+                stats.agreements,
+                stats.disagreements,
+                stats.challenges_lost,
+                stats.last_active,
+            ),
+        );
+    }
+
+    /// Add a confirmation's agreement score to the oracle's stats
+    fn score(stats: &mut OracleStats, confirmation: &ConfirmationData) {
+        match confirmation.agreed {
+            Some(true) => stats.agreements += 1,
+            Some(false) => stats.disagreements += 1,
+            None => {}
+        }
+    }
+
+    /// Remove a confirmation's agreement score from the oracle's stats
+    fn unscore(stats: &mut OracleStats, confirmation: &ConfirmationData) {
+        match confirmation.agreed {
+            Some(true) => stats.agreements = stats.agreements.saturating_sub(1),
+            Some(false) => stats.disagreements = stats.disagreements.saturating_sub(1),
+            None => {}
+        }
+    }
+
+    fn validate_payload(event_type: u32, payload: &ConfirmationPayload) -> Result<(), ContractError> {
+        let valid = match payload {
+            ConfirmationPayload::Shipment(_) => event_type == EventType::Shipment as u32,
+            ConfirmationPayload::Delivery(_) => event_type == EventType::Delivery as u32,
+            ConfirmationPayload::Quality(quality) => {
+                event_type == EventType::Quality as u32 && quality.grade <= 100
+            }
+            ConfirmationPayload::Custom(data) => {
+                event_type == EventType::Custom as u32 && !data.is_empty()
+            }
+            ConfirmationPayload::Valuation(valuation) => {
+                event_type == EventType::Valuation as u32 && valuation.amount > 0
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(ContractError::InvalidPayload)
+        }
+    }
+
+    fn create_message(
+        env: &Env,
+        escrow_contract: &Address,
+        escrow_id: &Bytes,
+        event_type: u32,
+        sequence: u64,
+        payload: &ConfirmationPayload,
+    ) -> BytesN<32> {
         // Create a deterministic message hash for signature verification
         let mut message_data = Bytes::new(env);
+        message_data.append(&escrow_contract.clone().to_xdr(env));
         message_data.append(escrow_id);
         message_data.append(&Bytes::from_slice(env, &event_type.to_be_bytes()));
-        message_data.append(result);
+        message_data.append(&Bytes::from_slice(env, &sequence.to_be_bytes()));
+        message_data.append(&payload.clone().to_xdr(env));
 
         env.crypto().sha256(&message_data).into()
     }
@@ -339,8 +1139,80 @@ impl OracleAdapter {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::{testutils::MockAuth, testutils::MockAuthInvoke, Address, Env, Bytes, IntoVal};
+    use soroban_sdk::testutils::{Address as _, Ledger as _};
+    use soroban_sdk::{testutils::MockAuth, testutils::MockAuthInvoke, vec, Address, Env, Bytes, IntoVal};
+
+    fn test_public_key(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[7; 32])
+    }
+
+    /// A valid payload for each event type
+    fn test_payload(env: &Env, event_type: u32) -> ConfirmationPayload {
+        match event_type {
+            1 => ConfirmationPayload::Shipment(ShipmentPayload {
+                carrier_code: Symbol::new(env, "MAEU"),
+                tracking_hash: BytesN::from_array(env, &[1; 32]),
+                port: Symbol::new(env, "NGLOS"),
+                status: ShipmentStatus::Departed,
+            }),
+            2 => ConfirmationPayload::Delivery(DeliveryPayload {
+                receiver_signoff_hash: BytesN::from_array(env, &[2; 32]),
+            }),
+            3 => quality_payload(env, 90),
+            5 => ConfirmationPayload::Valuation(ValuationPayload {
+                amount: 10_000,
+                currency: Symbol::new(env, "USD"),
+            }),
+            _ => ConfirmationPayload::Custom(Bytes::from_slice(env, b"confirmed")),
+        }
+    }
+
+    fn quality_payload(env: &Env, grade: u32) -> ConfirmationPayload {
+        ConfirmationPayload::Quality(QualityPayload {
+            grade,
+            inspection_cert_hash: BytesN::from_array(env, &[3; 32]),
+        })
+    }
+
+    /// Minimal escrow contract recording release callbacks
+    #[contract]
+    pub struct MockEscrow;
+
+    #[contractimpl]
+    impl MockEscrow {
+        pub fn release_funds(env: Env, escrow_id: u64) {
+            let count: u32 = env.storage().instance().get(&escrow_id).unwrap_or(0);
+            env.storage().instance().set(&escrow_id, &(count + 1));
+        }
+
+        pub fn release_count(env: Env, escrow_id: u64) -> u32 {
+            env.storage().instance().get(&escrow_id).unwrap_or(0)
+        }
+
+        pub fn release_milestone(env: Env, escrow_id: u64, event_type: u32) -> i128 {
+            let blocked: bool = env.storage().instance().get(&symbol_short!("ms_block")).unwrap_or(false);
+            if blocked {
+                panic!("milestone rejected");
+            }
+
+            let key = (symbol_short!("ms"), escrow_id);
+            let mut released: Vec<u32> = env.storage().instance().get(&key).unwrap_or(Vec::new(&env));
+            released.push_back(event_type);
+            env.storage().instance().set(&key, &released);
+            100
+        }
+
+        pub fn released_milestones(env: Env, escrow_id: u64) -> Vec<u32> {
+            env.storage()
+                .instance()
+                .get(&(symbol_short!("ms"), escrow_id))
+                .unwrap_or(Vec::new(&env))
+        }
+
+        pub fn set_blocked(env: Env, blocked: bool) {
+            env.storage().instance().set(&symbol_short!("ms_block"), &blocked);
+        }
+    }
 
     #[test]
     fn test_initialization() {
@@ -371,6 +1243,7 @@ mod test {
         let oracle1 = Address::generate(&env);
         let oracle2 = Address::generate(&env);
         let unauthorized = Address::generate(&env);
+        let all_types: Vec<u32> = Vec::new(&env);
 
         // Initialize
         client.initialize(&admin);
@@ -384,12 +1257,12 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle1.clone(),).into_val(&env),
+                args: (oracle1.clone(), test_public_key(&env), all_types.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        client.add_oracle(&oracle1);
-        assert_eq!(client.is_oracle_registered_query(&oracle1), true);
+        client.add_oracle(&oracle1, &test_public_key(&env), &all_types);
+        assert!(client.is_oracle_registered_query(&oracle1));
         assert_eq!(client.get_oracle_count(), 1);
 
         // Test adding second oracle
@@ -398,12 +1271,12 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle2.clone(),).into_val(&env),
+                args: (oracle2.clone(), test_public_key(&env), all_types.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        client.add_oracle(&oracle2);
-        assert_eq!(client.is_oracle_registered_query(&oracle2), true);
+        client.add_oracle(&oracle2, &test_public_key(&env), &all_types);
+        assert!(client.is_oracle_registered_query(&oracle2));
         assert_eq!(client.get_oracle_count(), 2);
 
         // Test adding same oracle fails
@@ -412,11 +1285,11 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (oracle1.clone(),).into_val(&env),
+                args: (oracle1.clone(), test_public_key(&env), all_types.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        assert_eq!(client.try_add_oracle(&oracle1), Err(Ok(ContractError::OracleAlreadyRegistered)));
+        assert_eq!(client.try_add_oracle(&oracle1, &test_public_key(&env), &all_types), Err(Ok(ContractError::OracleAlreadyRegistered)));
 
         // Test unauthorized add fails
         env.mock_auths(&[MockAuth {
@@ -424,11 +1297,11 @@ mod test {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "add_oracle",
-                args: (Address::generate(&env),).into_val(&env),
+                args: (Address::generate(&env), test_public_key(&env), all_types.clone()).into_val(&env),
                 sub_invokes: &[],
             },
         }]);
-        assert!(client.try_add_oracle(&Address::generate(&env)).is_err());
+        assert!(client.try_add_oracle(&Address::generate(&env), &test_public_key(&env), &all_types).is_err());
 
         // Test removing oracle
         env.mock_auths(&[MockAuth {
@@ -441,7 +1314,7 @@ mod test {
             },
         }]);
         client.remove_oracle(&oracle1);
-        assert_eq!(client.is_oracle_registered_query(&oracle1), false);
+        assert!(!client.is_oracle_registered_query(&oracle1));
         assert_eq!(client.get_oracle_count(), 1);
 
         // Test removing non-existent oracle fails
@@ -481,23 +1354,23 @@ mod test {
 
         // Initialize and add oracle
         client.initialize(&admin);
-        client.add_oracle(&oracle);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
-        let result = Bytes::from_slice(&env, b"confirmed");
+        let escrow_contract = Address::generate(&env);
         let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Test invalid event type (0)
-        assert_eq!(client.try_confirm_event(&oracle, &escrow_id, &0u32, &result, &signature),
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &0u32, &1u64, &test_payload(&env, 0), &signature),
                   Err(Ok(ContractError::InvalidEventType)));
 
         // Test invalid event type (6)
-        assert_eq!(client.try_confirm_event(&oracle, &escrow_id, &6u32, &result, &signature),
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &6u32, &1u64, &test_payload(&env, 6), &signature),
                   Err(Ok(ContractError::InvalidEventType)));
 
-        // Test valid event types (1-4)
-        for event_type in 1..=4 {
-            let confirm_result = client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature);
+        // Test valid event types (1-5)
+        for event_type in 1..=5 {
+            let confirm_result = client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &event_type, &1u64, &test_payload(&env, event_type), &signature);
             assert!(confirm_result.is_ok());
         }
     }
@@ -514,20 +1387,21 @@ mod test {
 
         // Initialize and add oracle
         client.initialize(&admin);
-        client.add_oracle(&oracle);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
         let event_type = 1u32;
-        let result = Bytes::from_slice(&env, b"confirmed");
+        let result = test_payload(&env, event_type);
         let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // First confirmation should work
         // Note: verify_signature is now just require_auth(), so it should pass with mock_all_auths
-        let confirm_result = client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature);
+        let confirm_result = client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &event_type, &1u64, &result, &signature);
         assert!(confirm_result.is_ok());
 
         // Second confirmation from same oracle should fail (replay attack)
-        assert_eq!(client.try_confirm_event(&oracle, &escrow_id, &event_type, &result, &signature),
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &event_type, &1u64, &result, &signature),
                   Err(Ok(ContractError::ConfirmationAlreadyExists)));
     }
 
@@ -544,12 +1418,13 @@ mod test {
         client.initialize(&admin);
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
         let event_type = 1u32;
-        let result = Bytes::from_slice(&env, b"confirmed");
+        let result = test_payload(&env, event_type);
         let signature = Bytes::from_slice(&env, b"mock_signature");
 
         // Confirmation from unregistered oracle should fail
-        assert_eq!(client.try_confirm_event(&unauthorized_oracle, &escrow_id, &event_type, &result, &signature),
+        assert_eq!(client.try_confirm_event(&unauthorized_oracle, &escrow_contract, &escrow_id, &event_type, &1u64, &result, &signature),
                   Err(Ok(ContractError::OracleNotRegistered)));
     }
 
@@ -565,9 +1440,10 @@ mod test {
         client.initialize(&admin);
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
 
         // Test getting confirmation for non-existent escrow
-        assert_eq!(client.get_confirmation(&escrow_id), None);
+        assert_eq!(client.get_confirmation(&escrow_contract, &escrow_id), None);
     }
 
     #[test]
@@ -588,14 +1464,14 @@ mod test {
         assert_eq!(client.get_oracle_count(), 0);
 
         // Add oracles
-        client.add_oracle(&oracle1);
-        client.add_oracle(&oracle2);
+        client.add_oracle(&oracle1, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle2, &test_public_key(&env), &Vec::new(&env));
         assert_eq!(client.get_oracle_count(), 2);
 
         // Test oracle registration queries
-        assert_eq!(client.is_oracle_registered_query(&oracle1), true);
-        assert_eq!(client.is_oracle_registered_query(&oracle2), true);
-        assert_eq!(client.is_oracle_registered_query(&Address::generate(&env)), false);
+        assert!(client.is_oracle_registered_query(&oracle1));
+        assert!(client.is_oracle_registered_query(&oracle2));
+        assert!(!client.is_oracle_registered_query(&Address::generate(&env)));
 
         // Test getting oracles by index
         let oracle_at_0 = client.get_oracle_at(&0);
@@ -613,13 +1489,474 @@ mod test {
         let contract_id = env.register(OracleAdapter, ());
 
         let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
         let event_type = 1u32;
-        let result = Bytes::from_slice(&env, b"confirmed");
+        let result = test_payload(&env, event_type);
 
         env.as_contract(&contract_id, || {
-            let message = OracleAdapter::create_message(&env, &escrow_id, event_type, &result);
+            let message = OracleAdapter::create_message(&env, &escrow_contract, &escrow_id, event_type, 1, &result);
             // Message should be a valid hash
             assert_eq!(message.len(), 32);
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_release_condition_triggers_escrow() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+        let escrow_client = MockEscrowClient::new(&env, &escrow_contract);
+
+        let admin = Address::generate(&env);
+        let oracle1 = Address::generate(&env);
+        let oracle2 = Address::generate(&env);
+
+        client.initialize(&admin);
+        client.add_oracle(&oracle1, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle2, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+
+        // Shipment and delivery must each be confirmed once
+        client.register_release_condition(&escrow_contract, &escrow_id, &7u64, &vec![&env, 1u32, 2u32], &1u32);
+
+        client.confirm_event(&oracle1, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(escrow_client.release_count(&7u64), 0);
+        assert!(!client.get_release_condition(&escrow_contract, &escrow_id).unwrap().released);
+
+        // Last required confirmation releases the escrow
+        client.confirm_event(&oracle2, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 2), &signature);
+        assert_eq!(escrow_client.release_count(&7u64), 1);
+        assert!(client.get_release_condition(&escrow_contract, &escrow_id).unwrap().released);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &2u32), 1);
+
+        // Released conditions are not triggered again
+        assert!(!client.trigger_release(&escrow_contract, &escrow_id));
+        assert_eq!(escrow_client.release_count(&7u64), 1);
+    }
+
+    #[test]
+    fn test_milestone_condition_releases_in_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+        let escrow_client = MockEscrowClient::new(&env, &escrow_contract);
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+
+        // Deposit on shipment, balance on delivery, holdback after inspection
+        client.register_milestone_condition(&escrow_contract, &escrow_id, &7u64, &vec![&env, 1u32, 2u32, 3u32], &1u32);
+
+        // Delivery confirmed ahead of shipment pays nothing yet
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 2), &signature);
+        assert_eq!(escrow_client.released_milestones(&7u64), Vec::new(&env));
+
+        // Shipment releases its own tranche and then the confirmed delivery tranche
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &2u64, &test_payload(&env, 1), &signature);
+        assert_eq!(escrow_client.released_milestones(&7u64), vec![&env, 1u32, 2u32]);
+        let condition = client.get_release_condition(&escrow_contract, &escrow_id).unwrap();
+        assert_eq!(condition.milestones_released, 2);
+        assert!(!condition.released);
+
+        // Only milestones are released; the full release is never called
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &3u64, &test_payload(&env, 3), &signature);
+        assert_eq!(escrow_client.released_milestones(&7u64), vec![&env, 1u32, 2u32, 3u32]);
+        assert_eq!(escrow_client.release_count(&7u64), 0);
+        assert!(client.get_release_condition(&escrow_contract, &escrow_id).unwrap().released);
+        assert!(!client.trigger_release(&escrow_contract, &escrow_id));
+    }
+
+    #[test]
+    fn test_rejected_milestone_can_be_retried() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+        let escrow_client = MockEscrowClient::new(&env, &escrow_contract);
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+        client.register_milestone_condition(&escrow_contract, &escrow_id, &7u64, &vec![&env, 1u32, 2u32], &1u32);
+
+        // The escrow rejects the tranche, so the milestone stays pending
+        escrow_client.set_blocked(&true);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(client.get_release_condition(&escrow_contract, &escrow_id).unwrap().milestones_released, 0);
+        assert!(!client.trigger_release(&escrow_contract, &escrow_id));
+
+        escrow_client.set_blocked(&false);
+        assert!(client.trigger_release(&escrow_contract, &escrow_id));
+        assert_eq!(escrow_client.released_milestones(&7u64), vec![&env, 1u32]);
+        assert_eq!(client.get_release_condition(&escrow_contract, &escrow_id).unwrap().milestones_released, 1);
+
+        // Nothing further until the next milestone is confirmed
+        assert!(!client.trigger_release(&escrow_contract, &escrow_id));
+    }
+
+    #[test]
+    fn test_release_condition_validation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+
+        // Quorum must be between 1 and the number of active oracles
+        assert_eq!(client.try_register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32], &0u32),
+                   Err(Ok(ContractError::InvalidQuorum)));
+        assert_eq!(client.try_register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32], &2u32),
+                   Err(Ok(ContractError::InvalidQuorum)));
+
+        let suspended = Address::generate(&env);
+        client.add_oracle(&suspended, &test_public_key(&env), &Vec::new(&env));
+        client.set_oracle_active(&suspended, &false);
+        assert_eq!(client.try_register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32], &2u32),
+                   Err(Ok(ContractError::InvalidQuorum)));
+        client.remove_oracle(&suspended);
+
+        // Event types must be valid
+        assert_eq!(client.try_register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 9u32], &1u32),
+                   Err(Ok(ContractError::InvalidEventType)));
+
+        client.register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32], &1u32);
+        assert_eq!(client.try_register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32], &1u32),
+                   Err(Ok(ContractError::ConditionAlreadyRegistered)));
+
+        // Escrow ids are scoped per escrow contract, so another contract's
+        // condition neither blocks nor shares confirmations with this one
+        let other_contract = env.register(MockEscrow, ());
+        client.register_release_condition(&other_contract, &escrow_id, &1u64, &vec![&env, 1u32], &1u32);
+        assert!(!client.get_release_condition(&escrow_contract, &escrow_id).unwrap().released);
+        assert!(!client.get_release_condition(&other_contract, &escrow_id).unwrap().released);
+
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert!(client.get_release_condition(&escrow_contract, &escrow_id).unwrap().released);
+        assert!(!client.get_release_condition(&other_contract, &escrow_id).unwrap().released);
+        assert_eq!(client.get_confirmation_count(&other_contract, &escrow_id, &1u32), 0);
+
+        // Unknown escrows cannot be triggered
+        assert_eq!(client.try_trigger_release(&escrow_contract, &Bytes::from_slice(&env, b"other")),
+                   Err(Ok(ContractError::ConditionNotFound)));
+    }
+
+    #[test]
+    fn test_confirmation_expiry_and_reattestation() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+
+        // Quality readings stay valid for one hour
+        client.set_validity_period(&3u32, &3600u64);
+        assert_eq!(client.get_validity_period(&3u32), 3600);
+        assert_eq!(client.get_validity_period(&1u32), 0);
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+
+        env.ledger().set_timestamp(1000);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 80), &signature);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &3u32), 1);
+        assert_eq!(client.get_confirmation(&escrow_contract, &escrow_id).unwrap().len(), 2);
+
+        // Quality reading goes stale, shipment never expires
+        env.ledger().set_timestamp(1000 + 3601);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &3u32), 0);
+        let confirmations = client.get_confirmation(&escrow_contract, &escrow_id).unwrap();
+        assert_eq!(confirmations.len(), 1);
+        assert_eq!(confirmations.get(0).unwrap().event_type, 1);
+
+        // Older or repeated sequences are rejected
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 80), &signature),
+                   Err(Ok(ContractError::ConfirmationAlreadyExists)));
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &0u64, &quality_payload(&env, 80), &signature),
+                   Err(Ok(ContractError::StaleSequence)));
+
+        // A newer attestation replaces the stale one
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &2u64, &quality_payload(&env, 95), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &3u32), 1);
+        let confirmations = client.get_confirmation(&escrow_contract, &escrow_id).unwrap();
+        assert_eq!(confirmations.len(), 2);
+        let quality = confirmations.get(1).unwrap();
+        assert_eq!(quality.sequence, 2);
+        assert_eq!(quality.payload, quality_payload(&env, 95));
+        assert_eq!(quality.expires_at, 1000 + 3601 + 3600);
+    }
+
+    #[test]
+    fn test_expired_confirmations_do_not_release() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+        let escrow_client = MockEscrowClient::new(&env, &escrow_contract);
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+        client.set_validity_period(&3u32, &600u64);
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+        client.register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 1u32, 3u32], &1u32);
+
+        env.ledger().set_timestamp(1000);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &1u64, &test_payload(&env, 3), &signature);
+
+        // Quality confirmation expires before shipment lands
+        env.ledger().set_timestamp(2000);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(escrow_client.release_count(&1u64), 0);
+
+        // Re-attesting quality completes the condition
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &2u64, &test_payload(&env, 3), &signature);
+        assert_eq!(escrow_client.release_count(&1u64), 1);
+    }
+
+    #[test]
+    fn test_oracle_stats() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+
+        let admin = Address::generate(&env);
+        let oracle1 = Address::generate(&env);
+        let oracle2 = Address::generate(&env);
+        let oracle3 = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle1, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle2, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle3, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+        client.register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 2u32], &2u32);
+
+        let dissent = ConfirmationPayload::Delivery(DeliveryPayload {
+            receiver_signoff_hash: BytesN::from_array(&env, &[9; 32]),
+        });
+
+        // The first confirmer dissents; a tie leaves both confirmations unscored
+        env.ledger().set_timestamp(500);
+        client.confirm_event(&oracle1, &escrow_contract, &escrow_id, &2u32, &1u64, &dissent, &signature);
+        client.confirm_event(&oracle2, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 2), &signature);
+        let stats = client.get_oracle_stats(&oracle1);
+        assert_eq!(stats.confirmations, 1);
+        assert_eq!((stats.agreements, stats.disagreements), (0, 0));
+        assert_eq!(stats.last_active, 500);
+        let stats = client.get_oracle_stats(&oracle2);
+        assert_eq!((stats.agreements, stats.disagreements), (0, 0));
+
+        // Once a majority forms, every confirmation is scored against it,
+        // whatever order they arrived in
+        client.confirm_event(&oracle3, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 2), &signature);
+        let dissenter = client.get_oracle_stats(&oracle1);
+        assert_eq!((dissenter.agreements, dissenter.disagreements), (0, 1));
+        let stats = client.get_oracle_stats(&oracle2);
+        assert_eq!((stats.agreements, stats.disagreements), (1, 0));
+        assert_eq!(client.get_oracle_stats(&oracle3).agreements, 1);
+
+        // An upheld challenge removes the confirmation and reverses its score
+        client.resolve_challenge(&escrow_contract, &escrow_id, &oracle1, &2u32, &true);
+        let dissenter = client.get_oracle_stats(&oracle1);
+        assert_eq!(dissenter.challenges_lost, 1);
+        assert_eq!(dissenter.disagreements, 0);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &2u32), 2);
+        assert_eq!(client.try_resolve_challenge(&escrow_contract, &escrow_id, &oracle1, &2u32, &true),
+                   Err(Ok(ContractError::ConfirmationNotFound)));
+
+        // A re-attestation that breaks the majority unscores the rest
+        client.confirm_event(&oracle2, &escrow_contract, &escrow_id, &2u32, &2u64, &dissent, &signature);
+        let stats = client.get_oracle_stats(&oracle2);
+        assert_eq!(stats.confirmations, 2);
+        assert_eq!((stats.agreements, stats.disagreements), (0, 0));
+        assert_eq!(client.get_oracle_stats(&oracle3).agreements, 0);
+
+        client.resolve_challenge(&escrow_contract, &escrow_id, &oracle2, &2u32, &true);
+        let stats = client.get_oracle_stats(&oracle2);
+        assert_eq!(stats.challenges_lost, 1);
+        assert_eq!(client.get_oracle_stats(&oracle3).agreements, 1);
+
+        // Unknown oracles have empty stats
+        assert_eq!(client.get_oracle_stats(&Address::generate(&env)), OracleStats::default());
+    }
+
+    #[test]
+    fn test_oracle_registry_metadata_and_listing() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        env.ledger().set_timestamp(42);
+        let mut oracles = Vec::new(&env);
+        for _ in 0..5 {
+            let oracle = Address::generate(&env);
+            client.add_oracle(&oracle, &test_public_key(&env), &vec![&env, 1u32, 2u32]);
+            oracles.push_back(oracle);
+        }
+
+        let info = client.get_oracle(&oracles.get(0).unwrap()).unwrap();
+        assert_eq!(info.public_key, test_public_key(&env));
+        assert_eq!(info.event_types, vec![&env, 1u32, 2u32]);
+        assert_eq!(info.added_at, 42);
+        assert!(info.active);
+        assert_eq!(info.index, 0);
+
+        // Paginated listing
+        let page = client.list_oracles(&0u32, &2u32);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page.get(1).unwrap().address, oracles.get(1).unwrap());
+        assert_eq!(client.list_oracles(&4u32, &10u32).len(), 1);
+        assert_eq!(client.list_oracles(&5u32, &10u32).len(), 0);
+
+        // Removing an oracle moves the last one into its slot
+        client.remove_oracle(&oracles.get(1).unwrap());
+        assert_eq!(client.get_oracle_count(), 4);
+        assert_eq!(client.get_oracle_at(&1u32), oracles.get(4));
+        assert_eq!(client.get_oracle(&oracles.get(4).unwrap()).unwrap().index, 1);
+        assert_eq!(client.get_oracle_at(&4u32), None);
+
+        // Unsupported event types are rejected
+        let oracle = oracles.get(0).unwrap();
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &3u32, &1u64, &test_payload(&env, 3), &signature),
+                   Err(Ok(ContractError::EventTypeNotSupported)));
+
+        // Suspended oracles cannot confirm and their confirmations stop counting
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &1u32), 1);
+        client.set_oracle_active(&oracle, &false);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &1u32), 0);
+        assert_eq!(client.try_confirm_event(&oracle, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 2), &signature),
+                   Err(Ok(ContractError::OracleInactive)));
+    }
+
+    #[test]
+    fn test_removed_oracle_confirmations_do_not_count() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let escrow_contract = Address::generate(&env);
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+
+        env.ledger().set_timestamp(1000);
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &5u64, &test_payload(&env, 1), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &1u32), 1);
+
+        // Re-adding a removed oracle does not revive its old confirmations
+        client.remove_oracle(&oracle);
+        env.ledger().set_timestamp(2000);
+        client.add_oracle(&oracle, &test_public_key(&env), &Vec::new(&env));
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &1u32), 0);
+        assert_eq!(client.get_confirmation(&escrow_contract, &escrow_id), None);
+
+        // The new registration attests afresh, whatever its old sequence was
+        client.confirm_event(&oracle, &escrow_contract, &escrow_id, &1u32, &1u64, &test_payload(&env, 1), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &1u32), 1);
+        let confirmation = client.get_confirmation(&escrow_contract, &escrow_id).unwrap().get(0).unwrap();
+        assert_eq!(confirmation.oracle_added_at, 2000);
+    }
+
+    #[test]
+    fn test_payload_validation_and_quorum_agreement() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(OracleAdapter, ());
+        let client = OracleAdapterClient::new(&env, &contract_id);
+        let escrow_contract = env.register(MockEscrow, ());
+        let escrow_client = MockEscrowClient::new(&env, &escrow_contract);
+
+        let admin = Address::generate(&env);
+        let oracle1 = Address::generate(&env);
+        let oracle2 = Address::generate(&env);
+        let oracle3 = Address::generate(&env);
+        client.initialize(&admin);
+        client.add_oracle(&oracle1, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle2, &test_public_key(&env), &Vec::new(&env));
+        client.add_oracle(&oracle3, &test_public_key(&env), &Vec::new(&env));
+
+        let escrow_id = Bytes::from_slice(&env, b"escrow_123");
+        let signature = Bytes::from_slice(&env, b"mock_signature");
+
+        // Payload variant must match the event type
+        assert_eq!(client.try_confirm_event(&oracle1, &escrow_contract, &escrow_id, &2u32, &1u64, &test_payload(&env, 1), &signature),
+                   Err(Ok(ContractError::InvalidPayload)));
+        // Field-level checks
+        assert_eq!(client.try_confirm_event(&oracle1, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 101), &signature),
+                   Err(Ok(ContractError::InvalidPayload)));
+        let zero_valuation = ConfirmationPayload::Valuation(ValuationPayload {
+            amount: 0,
+            currency: Symbol::new(&env, "USD"),
+        });
+        assert_eq!(client.try_confirm_event(&oracle1, &escrow_contract, &escrow_id, &5u32, &1u64, &zero_valuation, &signature),
+                   Err(Ok(ContractError::InvalidPayload)));
+
+        // Two matching quality grades are required
+        client.register_release_condition(&escrow_contract, &escrow_id, &1u64, &vec![&env, 3u32], &2u32);
+
+        client.confirm_event(&oracle1, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 90), &signature);
+        client.confirm_event(&oracle2, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 60), &signature);
+        assert_eq!(client.get_confirmation_count(&escrow_contract, &escrow_id, &3u32), 2);
+        assert_eq!(client.get_majority_confirmation(&escrow_contract, &escrow_id, &3u32), None);
+        assert_eq!(escrow_client.release_count(&1u64), 0);
+
+        // Agreement with the first oracle completes the quorum
+        client.confirm_event(&oracle3, &escrow_contract, &escrow_id, &3u32, &1u64, &quality_payload(&env, 90), &signature);
+        assert_eq!(escrow_client.release_count(&1u64), 1);
+        assert_eq!(client.get_oracle_stats(&oracle2).disagreements, 1);
+        assert_eq!(
+            client.get_majority_confirmation(&escrow_contract, &escrow_id, &3u32),
+            Some((quality_payload(&env, 90), 2))
+        );
+    }
+}